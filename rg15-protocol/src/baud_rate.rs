@@ -0,0 +1,83 @@
+use core::ffi::CStr;
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BaudRate {
+    Baud1200 = 0,
+    Baud2400 = 1,
+    Baud4800 = 2,
+    #[default]
+    Baud9600 = 3,
+    Baud19200 = 4,
+    Baud38400 = 5,
+    Baud57600 = 6,
+}
+
+impl BaudRate {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn rate(self) -> u16 {
+        match self {
+            BaudRate::Baud1200 => 1200,
+            BaudRate::Baud2400 => 2400,
+            BaudRate::Baud4800 => 4800,
+            BaudRate::Baud9600 => 9600,
+            BaudRate::Baud19200 => 19200,
+            BaudRate::Baud38400 => 38400,
+            BaudRate::Baud57600 => 57600,
+        }
+    }
+
+    /// Time in milliseconds it takes to transfer `bytes` at this rate, rounded
+    /// up.
+    ///
+    /// The RG-15 uses 8N1 framing, so every byte takes 10 bits on the wire.
+    pub fn transfer_millis(self, bytes: u32) -> u32 {
+        let millis = (bytes as u64 * 10 * 1000).div_ceil(self.rate() as u64);
+        millis.try_into().unwrap_or(u32::MAX)
+    }
+
+    pub fn rate_as_char(self) -> &'static CStr {
+        match self {
+            BaudRate::Baud1200 => c"1200",
+            BaudRate::Baud2400 => c"2400",
+            BaudRate::Baud4800 => c"4800",
+            BaudRate::Baud9600 => c"9600",
+            BaudRate::Baud19200 => c"19200",
+            BaudRate::Baud38400 => c"38400",
+            BaudRate::Baud57600 => c"57600",
+        }
+    }
+
+    pub fn list() -> [Self; 7] {
+        [
+            BaudRate::Baud1200,
+            BaudRate::Baud2400,
+            BaudRate::Baud4800,
+            BaudRate::Baud9600,
+            BaudRate::Baud19200,
+            BaudRate::Baud38400,
+            BaudRate::Baud57600,
+        ]
+    }
+}
+
+impl TryFrom<u16> for BaudRate {
+    type Error = u16;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        use BaudRate as BR;
+        Ok(match value {
+            0 | 1200 => BR::Baud1200,
+            1 | 2400 => BR::Baud2400,
+            2 | 4800 => BR::Baud4800,
+            3 | 9600 => BR::Baud9600,
+            4 | 19200 => BR::Baud19200,
+            5 | 38400 => BR::Baud38400,
+            6 | 57600 => BR::Baud57600,
+            _ => return Err(value),
+        })
+    }
+}
@@ -3,7 +3,9 @@ use core::fmt::Write;
 
 use alloc::{ffi::CString, string::String};
 
-#[derive(Debug, Clone, Copy)]
+use crate::{BaudRate, Response};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Command {
     AccumulationData,
     ReadAvailableData,
@@ -28,13 +30,13 @@ impl Command {
             C::AccumulationData,
             C::ReadAvailableData,
             C::Kill,
-            // C::BaudRate(BaudRate::Baud1200),
-            // C::BaudRate(BaudRate::Baud2400),
-            // C::BaudRate(BaudRate::Baud4800),
-            // C::BaudRate(BaudRate::Baud9600),
-            // C::BaudRate(BaudRate::Baud19200),
-            // C::BaudRate(BaudRate::Baud38400),
-            // C::BaudRate(BaudRate::Baud57600),
+            C::BaudRate(BaudRate::Baud1200),
+            C::BaudRate(BaudRate::Baud2400),
+            C::BaudRate(BaudRate::Baud4800),
+            C::BaudRate(BaudRate::Baud9600),
+            C::BaudRate(BaudRate::Baud19200),
+            C::BaudRate(BaudRate::Baud38400),
+            C::BaudRate(BaudRate::Baud57600),
             C::PollingMode,
             C::ContinousMode,
             C::ForceHighResolution,
@@ -124,104 +126,111 @@ impl Command {
         })
     }
 
-    pub fn cmd(&self) -> CString {
-        let mut s = String::new();
-        match self {
-            Command::AccumulationData => s.push('a'),
-            Command::ReadAvailableData => s.push('r'),
-            Command::Kill => s.push('k'),
-            Command::BaudRate(b) => write!(s, "b {}", b.code()).expect("infallible"),
-            Command::PollingMode => s.push('p'),
-            Command::ContinousMode => s.push('c'),
-            Command::ForceHighResolution => s.push('h'),
-            Command::ForceLowResolution => s.push('l'),
-            Command::ForceImperial => s.push('i'),
-            Command::ForceMetric => s.push('m'),
-            Command::UseSwitchValue => s.push('s'),
-            Command::ResetAccumulationCounter => s.push('o'),
-            Command::EnableExternalTbInput => s.push('x'),
-            Command::DisableExternalTbInput => s.push('y'),
+    /// Parses the text form of a command as sent over the wire, e.g. `r` or
+    /// `b 3`, the inverse of [`Command::cmd`].
+    ///
+    /// Like the RG-15 itself this is case insensitive and ignores surrounding
+    /// whitespace and line endings.
+    pub fn from_text(text: &str) -> Option<Command> {
+        let text = text.trim();
+        let mut chars = text.chars();
+        let letter = chars.next()?.to_ascii_lowercase();
+        let rest = chars.as_str().trim();
+
+        if letter == 'b' {
+            let code = rest.parse::<u8>().ok()?;
+            return match code {
+                0..=6 => BaudRate::try_from(code as u16).ok().map(Command::BaudRate),
+                _ => None,
+            };
+        }
+        if !rest.is_empty() {
+            return None;
         }
-        s.push_str("\r\n");
 
-        CString::new(s).expect("all possible options should create a valid c-string")
+        Some(match letter {
+            'a' => Command::AccumulationData,
+            'r' => Command::ReadAvailableData,
+            'k' => Command::Kill,
+            'p' => Command::PollingMode,
+            'c' => Command::ContinousMode,
+            'h' => Command::ForceHighResolution,
+            'l' => Command::ForceLowResolution,
+            'i' => Command::ForceImperial,
+            'm' => Command::ForceMetric,
+            's' => Command::UseSwitchValue,
+            'o' => Command::ResetAccumulationCounter,
+            'x' => Command::EnableExternalTbInput,
+            'y' => Command::DisableExternalTbInput,
+            _ => return None,
+        })
     }
-}
 
-#[repr(u8)]
-#[derive(Debug, Copy, Clone)]
-pub enum BaudRate {
-    Baud1200 = 0,
-    Baud2400 = 1,
-    Baud4800 = 2,
-    Baud9600 = 3, // Default
-    Baud19200 = 4,
-    Baud38400 = 5,
-    Baud57600 = 6,
-}
-
-impl Default for BaudRate {
-    fn default() -> Self {
-        BaudRate::Baud9600
+    /// The letter the command starts with, which is also what the RG-15
+    /// echoes for commands without a data response.
+    pub fn letter(&self) -> char {
+        match self {
+            Command::AccumulationData => 'a',
+            Command::ReadAvailableData => 'r',
+            Command::Kill => 'k',
+            Command::BaudRate(_) => 'b',
+            Command::PollingMode => 'p',
+            Command::ContinousMode => 'c',
+            Command::ForceHighResolution => 'h',
+            Command::ForceLowResolution => 'l',
+            Command::ForceImperial => 'i',
+            Command::ForceMetric => 'm',
+            Command::UseSwitchValue => 's',
+            Command::ResetAccumulationCounter => 'o',
+            Command::EnableExternalTbInput => 'x',
+            Command::DisableExternalTbInput => 'y',
+        }
     }
-}
 
-impl BaudRate {
-    pub fn code(self) -> u8 {
-        self as u8
+    /// Whether the command changes state the RG-15 keeps across power cycles
+    /// or resets, and so should only be sent after asking.
+    pub fn requires_confirmation(&self) -> bool {
+        matches!(
+            self,
+            Command::Kill
+                | Command::ResetAccumulationCounter
+                | Command::ForceHighResolution
+                | Command::ForceLowResolution
+                | Command::ForceImperial
+                | Command::ForceMetric
+                | Command::UseSwitchValue
+        )
     }
 
-    pub fn rate(self) -> u16 {
-        match self {
-            BaudRate::Baud1200 => 1200,
-            BaudRate::Baud2400 => 2400,
-            BaudRate::Baud4800 => 4800,
-            BaudRate::Baud9600 => 9600,
-            BaudRate::Baud19200 => 19200,
-            BaudRate::Baud38400 => 38400,
-            BaudRate::Baud57600 => 57600,
+    /// Whether `response` is the line the RG-15 answers this command with.
+    ///
+    /// Data commands are answered with a reading, [`Command::BaudRate`] with
+    /// its rate, [`Command::Kill`] with the banner of the restart and all
+    /// others with their echoed letter.
+    pub fn is_answered_by(&self, response: &Response) -> bool {
+        match (self, response) {
+            (Command::AccumulationData | Command::ReadAvailableData, response) => {
+                matches!(response, Response::Reading(_))
+            }
+            (Command::BaudRate(baud_rate), response) => {
+                matches!(response, Response::Baud(acknowledged) if acknowledged == baud_rate)
+            }
+            (Command::Kill, response) => {
+                matches!(response, Response::Other(line) if line.starts_with("RG-15"))
+            }
+            (cmd, Response::Echo(letter)) => cmd.letter() == *letter,
+            (_, _) => false,
         }
     }
 
-    pub fn rate_as_char(self) -> &'static CStr {
+    pub fn cmd(&self) -> CString {
+        let mut s = String::new();
         match self {
-            BaudRate::Baud1200 => c"1200",
-            BaudRate::Baud2400 => c"2400",
-            BaudRate::Baud4800 => c"4800",
-            BaudRate::Baud9600 => c"9600",
-            BaudRate::Baud19200 => c"19200",
-            BaudRate::Baud38400 => c"38400",
-            BaudRate::Baud57600 => c"57600",
+            Command::BaudRate(b) => write!(s, "b {}", b.code()).expect("infallible"),
+            cmd => s.push(cmd.letter()),
         }
-    }
-
-    pub fn list() -> [Self; 7] {
-        [
-            BaudRate::Baud1200,
-            BaudRate::Baud2400,
-            BaudRate::Baud4800,
-            BaudRate::Baud9600,
-            BaudRate::Baud19200,
-            BaudRate::Baud38400,
-            BaudRate::Baud57600,
-        ]
-    }
-}
-
-impl TryFrom<u16> for BaudRate {
-    type Error = u16;
+        s.push_str("\r\n");
 
-    fn try_from(value: u16) -> Result<Self, Self::Error> {
-        use BaudRate as BR;
-        Ok(match value {
-            0 | 1200 => BR::Baud1200,
-            1 | 2400 => BR::Baud2400,
-            2 | 4800 => BR::Baud4800,
-            3 | 9600 => BR::Baud9600,
-            4 | 19200 => BR::Baud19200,
-            5 | 38400 => BR::Baud38400,
-            6 | 57600 => BR::Baud57600,
-            _ => return Err(value),
-        })
+        CString::new(s).expect("all possible options should create a valid c-string")
     }
 }
@@ -0,0 +1,125 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{BaudRate, Command, Fixed, Measurement, Response, UnitSystem};
+
+/// What is known about the settings the RG-15 keeps in its non-volatile
+/// memory, learned from the lines it sends.
+///
+/// Everything starts out unknown, the sensor has no command to read its
+/// settings, so they are only learned as it reports or acknowledges them.
+/// Apart from the firmware in the power-up banner that's the
+/// acknowledgements, and for the mode the readings [`PollTracker`] finds
+/// nobody polled for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SensorConfig {
+    /// Firmware version from the power-up banner.
+    pub firmware: Option<Fixed>,
+    pub baud_rate: Option<BaudRate>,
+    pub high_resolution: Option<bool>,
+    pub polling: Option<bool>,
+    pub units: Option<UnitSystem>,
+    pub external_tb: Option<bool>,
+    pub total_acc: Option<Measurement>,
+}
+
+impl SensorConfig {
+    /// Takes over whatever `response` tells about the settings.
+    ///
+    /// Readings carry the units and the total accumulation, acknowledgements
+    /// the setting of the command they answer.
+    pub fn observe(&mut self, response: &Response) {
+        match *response {
+            Response::Reading(reading) => {
+                self.units = reading.unit_system().or(self.units);
+                self.total_acc = reading.total_acc.or(self.total_acc);
+            }
+            Response::Echo(letter) => {
+                let mut text = [0; 4];
+                if let Some(cmd) = Command::from_text(letter.encode_utf8(&mut text)) {
+                    self.acknowledged(cmd);
+                }
+            }
+            Response::Baud(baud_rate) => self.baud_rate = Some(baud_rate),
+            Response::Other(line) => {
+                if let Some(version) = line.strip_prefix("RG-15 v") {
+                    self.firmware = Fixed::parse(version.trim()).ok();
+                }
+            }
+        }
+    }
+
+    /// Takes over the change `cmd` makes once the sensor acknowledged it.
+    pub fn acknowledged(&mut self, cmd: Command) {
+        match cmd {
+            Command::AccumulationData | Command::ReadAvailableData | Command::Kill => (),
+            Command::BaudRate(baud_rate) => self.baud_rate = Some(baud_rate),
+            Command::PollingMode => self.polling = Some(true),
+            Command::ContinousMode => self.polling = Some(false),
+            Command::ForceHighResolution => self.high_resolution = Some(true),
+            Command::ForceLowResolution => self.high_resolution = Some(false),
+            Command::ForceImperial => self.units = Some(UnitSystem::Imperial),
+            Command::ForceMetric => self.units = Some(UnitSystem::Metric),
+            // whatever the DIP switches say, which we can't see
+            Command::UseSwitchValue => {
+                self.units = None;
+                self.high_resolution = None;
+            }
+            Command::ResetAccumulationCounter => {
+                self.total_acc = self.total_acc.map(|total_acc| Measurement {
+                    value: Fixed::from_thousandths(0, total_acc.value.decimals()),
+                    ..total_acc
+                });
+            }
+            Command::EnableExternalTbInput => self.external_tb = Some(true),
+            Command::DisableExternalTbInput => self.external_tb = Some(false),
+        }
+    }
+
+    /// Takes over what `response` tells about the settings like
+    /// [`observe`](Self::observe), and that the sensor is in continuous mode
+    /// if it's a reading nobody polled for.
+    pub fn observe_unpolled(&mut self, response: &Response, polls: &PollTracker) {
+        self.observe(response);
+        if polls.is_unpolled(response) {
+            self.polling = Some(false);
+        }
+    }
+}
+
+/// Counts the polls still waiting for their reading, to tell them apart from
+/// readings the sensor sends on its own in continuous mode.
+///
+/// Shared between whoever sends the commands and whoever reads the answers.
+#[derive(Debug, Default)]
+pub struct PollTracker {
+    outstanding: AtomicU32,
+}
+
+impl PollTracker {
+    pub const fn new() -> Self {
+        Self {
+            outstanding: AtomicU32::new(0),
+        }
+    }
+
+    /// Counts `cmd` if the sensor answers it with a reading.
+    pub fn sent(&self, cmd: Command) {
+        if matches!(cmd, Command::AccumulationData | Command::ReadAvailableData) {
+            self.outstanding.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether `response` is a reading nobody polled for, otherwise a reading
+    /// answers the oldest outstanding poll.
+    ///
+    /// A poll the sensor never answered is only written off by the next
+    /// reading, so that one isn't taken as unpolled.
+    pub fn is_unpolled(&self, response: &Response) -> bool {
+        if !matches!(response, Response::Reading(_)) {
+            return false;
+        }
+        self.outstanding
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_err()
+    }
+}
@@ -0,0 +1,24 @@
+//! Serial protocol of the Hydreon RG-15 rain gauge.
+//!
+//! This crate is `no_std` and has no Flipper dependencies so that it can be
+//! tested on the host with `cargo test-protocol`.
+
+#![no_std]
+
+extern crate alloc;
+
+mod baud_rate;
+mod command;
+mod config;
+mod reading;
+mod response;
+mod script;
+mod simulator;
+
+pub use baud_rate::*;
+pub use command::*;
+pub use config::*;
+pub use reading::*;
+pub use response::*;
+pub use script::*;
+pub use simulator::*;
@@ -0,0 +1,270 @@
+use core::fmt;
+
+/// A single response line of the RG-15 decoded into typed values.
+///
+/// Not every response carries every field, e.g. the `a` command only reports
+/// `Acc`, so all fields are optional.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub acc: Option<Measurement>,
+    pub event_acc: Option<Measurement>,
+    pub total_acc: Option<Measurement>,
+    pub r_int: Option<Measurement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    pub value: Fixed,
+    pub unit: Unit,
+}
+
+/// Fixed-point number with three decimal places.
+///
+/// The RG-15 never reports more than three decimals (`0.001 in` in high
+/// resolution imperial mode), so thousandths are enough to be lossless.
+/// The number of decimals as sent by the sensor is kept for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed {
+    thousandths: u32,
+    decimals: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Millimeters,
+    Inches,
+    MillimetersPerHour,
+    InchesPerHour,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The line contains no fields at all.
+    Empty,
+    /// A field key is not one the RG-15 sends.
+    UnknownField,
+    /// A key was sent twice in the same line.
+    DuplicateField,
+    /// The line ended after a key without a value.
+    MissingValue,
+    /// The line ended after a value without a unit.
+    MissingUnit,
+    InvalidNumber,
+    InvalidUnit,
+    /// An accumulation with a rate unit or an intensity with a length unit.
+    UnitMismatch,
+}
+
+impl Reading {
+    /// Parses a response line like
+    /// `Acc  0.01 mm, EventAcc  0.02 mm, TotalAcc  0.03 mm, RInt  0.04 mmph`.
+    pub fn parse(line: &str) -> Result<Reading, ParseError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let mut reading = Reading::default();
+        for field in line.split(',') {
+            let mut parts = field.split_whitespace();
+            let Some(key) = parts.next() else {
+                return Err(ParseError::MissingValue);
+            };
+            let slot = match key {
+                "Acc" => &mut reading.acc,
+                "EventAcc" => &mut reading.event_acc,
+                "TotalAcc" => &mut reading.total_acc,
+                "RInt" => &mut reading.r_int,
+                _ => return Err(ParseError::UnknownField),
+            };
+            if slot.is_some() {
+                return Err(ParseError::DuplicateField);
+            }
+
+            let value = parts.next().ok_or(ParseError::MissingValue)?;
+            let value = Fixed::parse(value)?;
+            let unit = parts.next().ok_or(ParseError::MissingUnit)?;
+            let unit = Unit::parse(unit)?;
+            if parts.next().is_some() {
+                return Err(ParseError::InvalidUnit);
+            }
+            if unit.is_rate() != (key == "RInt") {
+                return Err(ParseError::UnitMismatch);
+            }
+
+            *slot = Some(Measurement { value, unit });
+        }
+
+        Ok(reading)
+    }
+
+    /// Overwrites every field that is present in `other`.
+    pub fn merge(&mut self, other: Reading) {
+        self.acc = other.acc.or(self.acc);
+        self.event_acc = other.event_acc.or(self.event_acc);
+        self.total_acc = other.total_acc.or(self.total_acc);
+        self.r_int = other.r_int.or(self.r_int);
+    }
+
+    pub fn unit_system(&self) -> Option<UnitSystem> {
+        [self.acc, self.event_acc, self.total_acc, self.r_int]
+            .into_iter()
+            .flatten()
+            .map(|measurement| measurement.unit.system())
+            .next()
+    }
+}
+
+impl Measurement {
+    /// Converts into the units of `system`, with the decimals the RG-15 uses
+    /// for them.
+    pub fn to_system(self, system: UnitSystem) -> Self {
+        if self.unit.system() == system {
+            return self;
+        }
+
+        let thousandths = self.value.thousandths as u64;
+        let (thousandths, decimals) = match system {
+            // 1 in = 25.4 mm, rounded to the nearest step of the decimals
+            UnitSystem::Imperial => ((thousandths * 10 + 127) / 254, 3),
+            UnitSystem::Metric => ((thousandths * 254 + 50) / 100 * 10, 2),
+        };
+        Self {
+            value: Fixed::from_thousandths(thousandths.min(u32::MAX as u64) as u32, decimals),
+            unit: self.unit.to_system(system),
+        }
+    }
+}
+
+impl Fixed {
+    pub const fn from_thousandths(thousandths: u32, decimals: u8) -> Self {
+        Self {
+            thousandths,
+            decimals,
+        }
+    }
+
+    pub fn thousandths(self) -> u32 {
+        self.thousandths
+    }
+
+    pub fn decimals(self) -> u8 {
+        self.decimals
+    }
+
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() || frac.len() > 3 {
+            return Err(ParseError::InvalidNumber);
+        }
+        if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::InvalidNumber);
+        }
+
+        let int: u32 = int.parse().map_err(|_| ParseError::InvalidNumber)?;
+        let mut thousandths = int.checked_mul(1000).ok_or(ParseError::InvalidNumber)?;
+        let mut scale = 100;
+        for digit in frac.bytes() {
+            thousandths = thousandths
+                .checked_add((digit - b'0') as u32 * scale)
+                .ok_or(ParseError::InvalidNumber)?;
+            scale /= 10;
+        }
+
+        Ok(Self {
+            thousandths,
+            decimals: frac.len() as u8,
+        })
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let int = self.thousandths / 1000;
+        let frac = self.thousandths % 1000;
+        match self.decimals {
+            0 => write!(f, "{int}"),
+            1 => write!(f, "{int}.{:01}", frac / 100),
+            2 => write!(f, "{int}.{:02}", frac / 10),
+            _ => write!(f, "{int}.{frac:03}"),
+        }
+    }
+}
+
+impl Unit {
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        Ok(match s {
+            "mm" => Unit::Millimeters,
+            "in" => Unit::Inches,
+            "mmph" => Unit::MillimetersPerHour,
+            "iph" => Unit::InchesPerHour,
+            _ => return Err(ParseError::InvalidUnit),
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Unit::Millimeters => "mm",
+            Unit::Inches => "in",
+            Unit::MillimetersPerHour => "mmph",
+            Unit::InchesPerHour => "iph",
+        }
+    }
+
+    pub fn is_rate(self) -> bool {
+        matches!(self, Unit::MillimetersPerHour | Unit::InchesPerHour)
+    }
+
+    /// The unit measuring the same quantity in `system`.
+    pub fn to_system(self, system: UnitSystem) -> Self {
+        match (self.is_rate(), system) {
+            (false, UnitSystem::Metric) => Unit::Millimeters,
+            (false, UnitSystem::Imperial) => Unit::Inches,
+            (true, UnitSystem::Metric) => Unit::MillimetersPerHour,
+            (true, UnitSystem::Imperial) => Unit::InchesPerHour,
+        }
+    }
+
+    pub fn system(self) -> UnitSystem {
+        match self {
+            Unit::Millimeters | Unit::MillimetersPerHour => UnitSystem::Metric,
+            Unit::Inches | Unit::InchesPerHour => UnitSystem::Imperial,
+        }
+    }
+}
+
+/// Formats the reading the way the RG-15 sends it, without line ending.
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("Acc", self.acc),
+            ("EventAcc", self.event_acc),
+            ("TotalAcc", self.total_acc),
+            ("RInt", self.r_int),
+        ];
+        let mut first = true;
+        for (key, measurement) in fields {
+            let Some(measurement) = measurement else {
+                continue;
+            };
+            if !first {
+                f.write_str(", ")?;
+            }
+            write!(f, "{key} {measurement}")?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit.as_str())
+    }
+}
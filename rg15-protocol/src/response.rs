@@ -0,0 +1,56 @@
+use crate::{BaudRate, ParseError, Reading};
+
+/// Any line the RG-15 may send, classified by its shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response<'l> {
+    /// Measurement values, sent for `a`, `r` and in continuous mode.
+    Reading(Reading),
+    /// The single letter echo the RG-15 sends to acknowledge a command.
+    Echo(char),
+    /// Acknowledgement of a `b N` command, sent as `Baud <rate>`.
+    Baud(BaudRate),
+    /// Anything else, e.g. the power-up banner.
+    Other(&'l str),
+}
+
+impl<'l> Response<'l> {
+    /// Classifies a single line, surrounding whitespace and line endings are
+    /// ignored.
+    ///
+    /// Lines that start like a reading but fail to parse as one are reported
+    /// as error instead of [`Response::Other`] so that corrupted data doesn't
+    /// go unnoticed.
+    pub fn parse(line: &'l str) -> Result<Self, ParseError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let first = line.split_whitespace().next().unwrap_or_default();
+        if matches!(first, "Acc" | "EventAcc" | "TotalAcc" | "RInt") {
+            return Reading::parse(line).map(Response::Reading);
+        }
+
+        let mut chars = line.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if c.is_ascii_alphabetic() {
+                return Ok(Response::Echo(c.to_ascii_lowercase()));
+            }
+        }
+
+        if let Some(rate) = line.strip_prefix("Baud") {
+            let rate = rate
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| ParseError::InvalidNumber)?;
+            // only actual rates, `try_from` would also take the codes
+            let baud_rate = BaudRate::list()
+                .into_iter()
+                .find(|baud_rate| baud_rate.rate() == rate)
+                .ok_or(ParseError::InvalidNumber)?;
+            return Ok(Response::Baud(baud_rate));
+        }
+
+        Ok(Response::Other(line))
+    }
+}
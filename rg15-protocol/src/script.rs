@@ -0,0 +1,66 @@
+use crate::Command;
+
+/// A single line of a script.
+///
+/// Scripts have one step per line:
+///
+/// - a command in its text form, e.g. `r` or `b 3`, see [`Command::from_text`]
+/// - `wait <duration>` pauses, e.g. `wait 500ms` or `wait 2s`
+/// - `expect <text>` waits for a line starting with `<text>`, e.g.
+///   `expect Acc`
+/// - `log <text>` writes `<text>` into the transcript
+///
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptStep<'l> {
+    Send(Command),
+    /// Pause in milliseconds.
+    Wait(u32),
+    Expect(&'l str),
+    Log(&'l str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptError {
+    UnknownCommand,
+    InvalidDuration,
+    MissingText,
+}
+
+impl<'l> ScriptStep<'l> {
+    /// Parses a single script line, `None` for empty lines and comments.
+    pub fn parse(line: &'l str) -> Result<Option<Self>, ScriptError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let step = match keyword {
+            "wait" => ScriptStep::Wait(parse_duration(rest)?),
+            "expect" | "log" if rest.is_empty() => return Err(ScriptError::MissingText),
+            "expect" => ScriptStep::Expect(rest),
+            "log" => ScriptStep::Log(rest),
+            _ => ScriptStep::Send(Command::from_text(line).ok_or(ScriptError::UnknownCommand)?),
+        };
+        Ok(Some(step))
+    }
+}
+
+/// Parses durations like `500ms` or `2s` into milliseconds.
+fn parse_duration(text: &str) -> Result<u32, ScriptError> {
+    let (value, factor) = match text.strip_suffix("ms") {
+        Some(millis) => (millis, 1),
+        None => match text.strip_suffix('s') {
+            Some(seconds) => (seconds, 1000),
+            None => return Err(ScriptError::InvalidDuration),
+        },
+    };
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .and_then(|value| value.checked_mul(factor))
+        .ok_or(ScriptError::InvalidDuration)
+}
@@ -0,0 +1,259 @@
+use core::{ffi::CStr, fmt::Write};
+
+use alloc::string::String;
+
+use crate::{BaudRate, Command, Fixed, Measurement, Reading, Unit, UnitSystem};
+
+/// First line the RG-15 sends after power-up or a [`Command::Kill`].
+pub const BANNER: &str = "RG-15 v1.000";
+
+/// Time without rain in ms after which the RG-15 considers a rain event over.
+const EVENT_TIMEOUT: u32 = 60 * 60 * 1000;
+
+const MILLIS_PER_HOUR: u64 = 60 * 60 * 1000;
+
+/// Scripted rain for the [`SimulatedSensor`], all scenarios loop forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    Dry,
+    Drizzle,
+    Shower,
+    Storm,
+}
+
+impl Scenario {
+    pub fn list() -> [Self; 4] {
+        [
+            Scenario::Dry,
+            Scenario::Drizzle,
+            Scenario::Shower,
+            Scenario::Storm,
+        ]
+    }
+
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Scenario::Dry => c"Dry",
+            Scenario::Drizzle => c"Drizzle",
+            Scenario::Shower => c"Passing Showers",
+            Scenario::Storm => c"Thunderstorm",
+        }
+    }
+
+    /// Rain intensity in µm (thousandths of mm) per hour at `millis` into the
+    /// scenario.
+    pub fn intensity(self, millis: u32) -> u32 {
+        const MINUTE: u32 = 60 * 1000;
+        match self {
+            Scenario::Dry => 0,
+            // light and steady
+            Scenario::Drizzle => 400 + wobble(millis / MINUTE, 200),
+            // 10 minutes of rain rising to 8 mm/h and falling again, then 10
+            // minutes dry
+            Scenario::Shower => {
+                let t = millis % (20 * MINUTE);
+                match t < 10 * MINUTE {
+                    true => triangle(t, 10 * MINUTE, 8000),
+                    false => 0,
+                }
+            }
+            // 30 minutes of heavy gusts peaking at 60 mm/h, then 30 minutes of
+            // light rain
+            Scenario::Storm => {
+                let t = millis % (60 * MINUTE);
+                match t < 30 * MINUTE {
+                    true => {
+                        let base = triangle(t, 30 * MINUTE, 45000);
+                        base + wobble(millis / (10 * 1000), 15000)
+                    }
+                    false => 1000 + wobble(millis / MINUTE, 500),
+                }
+            }
+        }
+    }
+}
+
+/// Rises linearly from 0 to `peak` in the first half of `period` and falls
+/// back to 0 in the second half.
+fn triangle(t: u32, period: u32, peak: u32) -> u32 {
+    let half = period / 2;
+    let t = t % period;
+    let rising = match t < half {
+        true => t,
+        false => period - t,
+    };
+    (rising as u64 * peak as u64 / half as u64) as u32
+}
+
+/// Deterministic pseudo random value in `0..amplitude` for the `step`.
+fn wobble(step: u32, amplitude: u32) -> u32 {
+    let hash = step.wrapping_mul(2654435761) ^ (step >> 7);
+    hash % amplitude.max(1)
+}
+
+/// An RG-15 in software.
+///
+/// It answers every [`Command`] like the real sensor and rains according to
+/// a [`Scenario`]. Everything it sends is appended to an output string
+/// including line endings.
+#[derive(Debug, Clone)]
+pub struct SimulatedSensor {
+    pub baud_rate: BaudRate,
+    pub polling: bool,
+    pub high_resolution: bool,
+    pub units: UnitSystem,
+    pub external_tb: bool,
+    scenario: Scenario,
+    elapsed: u32,
+    dry_for: u32,
+    // accumulations in µm times ms per hour to not lose rain between ticks
+    acc: u64,
+    event_acc: u64,
+    total_acc: u64,
+    intensity: u32,
+}
+
+impl SimulatedSensor {
+    /// Values the DIP switches select when sending [`Command::UseSwitchValue`].
+    const SWITCH_UNITS: UnitSystem = UnitSystem::Metric;
+    const SWITCH_HIGH_RESOLUTION: bool = true;
+
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            baud_rate: BaudRate::default(),
+            polling: true,
+            high_resolution: Self::SWITCH_HIGH_RESOLUTION,
+            units: Self::SWITCH_UNITS,
+            external_tb: false,
+            scenario,
+            elapsed: 0,
+            dry_for: 0,
+            acc: 0,
+            event_acc: 0,
+            total_acc: 0,
+            intensity: 0,
+        }
+    }
+
+    /// Sends the power-up banner.
+    pub fn power_up(&mut self, out: &mut String) {
+        self.acc = 0;
+        self.event_acc = 0;
+        line(out, format_args!("{BANNER}"));
+    }
+
+    /// Lets `millis` pass, in continuous mode a reading is sent whenever the
+    /// accumulation grew by at least one resolution step.
+    pub fn advance(&mut self, millis: u32, out: &mut String) {
+        // sampling in the middle keeps long steps close to the scenario
+        self.intensity = self
+            .scenario
+            .intensity(self.elapsed.wrapping_add(millis / 2));
+        self.elapsed = self.elapsed.wrapping_add(millis);
+
+        let rain = self.intensity as u64 * millis as u64;
+        self.acc += rain;
+        self.event_acc += rain;
+        self.total_acc += rain;
+
+        match self.intensity {
+            0 => self.dry_for = self.dry_for.saturating_add(millis),
+            _ => self.dry_for = 0,
+        }
+        if self.dry_for >= EVENT_TIMEOUT {
+            self.event_acc = 0;
+        }
+
+        if !self.polling && self.quantize(self.acc).thousandths() > 0 {
+            self.send_reading(true, out);
+        }
+    }
+
+    /// Handles a line received from the host.
+    ///
+    /// Unknown commands are ignored, just like the real sensor does.
+    pub fn receive(&mut self, text: &str, out: &mut String) {
+        let Some(cmd) = Command::from_text(text) else {
+            return;
+        };
+
+        match cmd {
+            Command::AccumulationData => self.send_reading(false, out),
+            Command::ReadAvailableData => self.send_reading(true, out),
+            Command::Kill => return self.power_up(out),
+            Command::BaudRate(baud_rate) => {
+                // acknowledged at the old rate, switched afterwards
+                line(out, format_args!("Baud {}", baud_rate.rate()));
+                self.baud_rate = baud_rate;
+                return;
+            }
+            Command::PollingMode => self.polling = true,
+            Command::ContinousMode => self.polling = false,
+            Command::ForceHighResolution => self.high_resolution = true,
+            Command::ForceLowResolution => self.high_resolution = false,
+            Command::ForceImperial => self.units = UnitSystem::Imperial,
+            Command::ForceMetric => self.units = UnitSystem::Metric,
+            Command::UseSwitchValue => {
+                self.units = Self::SWITCH_UNITS;
+                self.high_resolution = Self::SWITCH_HIGH_RESOLUTION;
+            }
+            Command::ResetAccumulationCounter => {
+                self.acc = 0;
+                self.event_acc = 0;
+                self.total_acc = 0;
+            }
+            Command::EnableExternalTbInput => self.external_tb = true,
+            Command::DisableExternalTbInput => self.external_tb = false,
+        }
+
+        if !matches!(cmd, Command::AccumulationData | Command::ReadAvailableData) {
+            line(out, format_args!("{}", cmd.letter()));
+        }
+    }
+
+    fn send_reading(&mut self, all: bool, out: &mut String) {
+        let (length, rate) = match self.units {
+            UnitSystem::Metric => (Unit::Millimeters, Unit::MillimetersPerHour),
+            UnitSystem::Imperial => (Unit::Inches, Unit::InchesPerHour),
+        };
+        let measurement = |value, unit| Some(Measurement { value, unit });
+
+        let acc = self.quantize(self.acc);
+        let mut reading = Reading {
+            acc: measurement(acc, length),
+            ..Reading::default()
+        };
+        if all {
+            reading.event_acc = measurement(self.quantize(self.event_acc), length);
+            reading.total_acc = measurement(self.quantize(self.total_acc), length);
+            let intensity = self.intensity as u64 * MILLIS_PER_HOUR;
+            reading.r_int = measurement(self.quantize(intensity), rate);
+        }
+        line(out, format_args!("{reading}"));
+
+        // only what was reported is taken out, the rest of a step stays
+        let reported = match self.units {
+            UnitSystem::Metric => acc.thousandths() as u64,
+            UnitSystem::Imperial => acc.thousandths() as u64 * 254 / 10,
+        };
+        self.acc = self.acc.saturating_sub(reported * MILLIS_PER_HOUR);
+    }
+
+    /// Converts a raw accumulation into the reported units and resolution.
+    fn quantize(&self, raw: u64) -> Fixed {
+        let micrometers = raw / MILLIS_PER_HOUR;
+        let (thousandths, step, decimals) = match (self.units, self.high_resolution) {
+            (UnitSystem::Metric, true) => (micrometers, 10, 2),
+            (UnitSystem::Metric, false) => (micrometers, 200, 2),
+            (UnitSystem::Imperial, true) => (micrometers * 10 / 254, 1, 3),
+            (UnitSystem::Imperial, false) => (micrometers * 10 / 254, 10, 3),
+        };
+        let thousandths = (thousandths / step * step).min(u32::MAX as u64) as u32;
+        Fixed::from_thousandths(thousandths, decimals)
+    }
+}
+
+fn line(out: &mut String, args: core::fmt::Arguments<'_>) {
+    out.write_fmt(args).expect("infallible");
+    out.push_str("\r\n");
+}
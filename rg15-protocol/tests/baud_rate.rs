@@ -0,0 +1,45 @@
+use rg15_protocol::BaudRate;
+
+#[test]
+fn default_is_9600() {
+    assert_eq!(BaudRate::default(), BaudRate::Baud9600);
+    assert_eq!(BaudRate::default().rate(), 9600);
+}
+
+#[test]
+fn list_is_sorted_by_code() {
+    for (i, baud_rate) in BaudRate::list().into_iter().enumerate() {
+        assert_eq!(baud_rate.code() as usize, i);
+    }
+}
+
+#[test]
+fn try_from_accepts_codes_and_rates() {
+    for baud_rate in BaudRate::list() {
+        assert_eq!(BaudRate::try_from(baud_rate.code() as u16), Ok(baud_rate));
+        assert_eq!(BaudRate::try_from(baud_rate.rate()), Ok(baud_rate));
+    }
+}
+
+#[test]
+fn try_from_rejects_unknown_values() {
+    assert_eq!(BaudRate::try_from(7), Err(7));
+    assert_eq!(BaudRate::try_from(115200u32 as u16), Err(115200u32 as u16));
+    assert_eq!(BaudRate::try_from(300), Err(300));
+}
+
+#[test]
+fn rate_as_char_matches_rate() {
+    for baud_rate in BaudRate::list() {
+        let text = baud_rate.rate_as_char().to_str().unwrap();
+        assert_eq!(text.parse::<u16>().unwrap(), baud_rate.rate());
+    }
+}
+
+#[test]
+fn transfer_millis_rounds_up() {
+    assert_eq!(BaudRate::Baud1200.transfer_millis(120), 1000);
+    assert_eq!(BaudRate::Baud9600.transfer_millis(1), 2);
+    assert_eq!(BaudRate::Baud57600.transfer_millis(0), 0);
+    assert_eq!(BaudRate::Baud1200.transfer_millis(u32::MAX), u32::MAX);
+}
@@ -0,0 +1,146 @@
+use rg15_protocol::{BaudRate, Command, Response, Scenario, SimulatedSensor};
+
+fn all_commands() -> impl Iterator<Item = Command> {
+    Command::list().iter().copied()
+}
+
+#[test]
+fn code_round_trips() {
+    for cmd in all_commands() {
+        assert_eq!(Command::try_from_code(cmd.code()), Some(cmd));
+    }
+}
+
+#[test]
+fn codes_are_unique() {
+    let codes: Vec<u32> = all_commands().map(|cmd| cmd.code()).collect();
+    for (i, code) in codes.iter().enumerate() {
+        assert!(!codes[i + 1..].contains(code), "duplicate code {code}");
+    }
+}
+
+#[test]
+fn unknown_code_is_rejected() {
+    assert_eq!(Command::try_from_code(14), None);
+    assert_eq!(Command::try_from_code(1200), None);
+    assert_eq!(Command::try_from_code(u32::MAX), None);
+}
+
+#[test]
+fn list_has_all_baud_rate_commands() {
+    for baud_rate in BaudRate::list() {
+        assert!(Command::list().contains(&Command::BaudRate(baud_rate)));
+    }
+}
+
+#[test]
+fn encodes_single_letter_commands() {
+    let expected = [
+        (Command::AccumulationData, "a\r\n"),
+        (Command::ReadAvailableData, "r\r\n"),
+        (Command::Kill, "k\r\n"),
+        (Command::PollingMode, "p\r\n"),
+        (Command::ContinousMode, "c\r\n"),
+        (Command::ForceHighResolution, "h\r\n"),
+        (Command::ForceLowResolution, "l\r\n"),
+        (Command::ForceImperial, "i\r\n"),
+        (Command::ForceMetric, "m\r\n"),
+        (Command::UseSwitchValue, "s\r\n"),
+        (Command::ResetAccumulationCounter, "o\r\n"),
+        (Command::EnableExternalTbInput, "x\r\n"),
+        (Command::DisableExternalTbInput, "y\r\n"),
+    ];
+    for (cmd, encoded) in expected {
+        assert_eq!(cmd.cmd().to_str().unwrap(), encoded);
+    }
+}
+
+#[test]
+fn encodes_baud_rate_commands_with_code() {
+    assert_eq!(
+        Command::BaudRate(BaudRate::Baud1200)
+            .cmd()
+            .to_str()
+            .unwrap(),
+        "b 0\r\n"
+    );
+    assert_eq!(
+        Command::BaudRate(BaudRate::Baud57600)
+            .cmd()
+            .to_str()
+            .unwrap(),
+        "b 6\r\n"
+    );
+}
+
+#[test]
+fn names_are_unique() {
+    let names: Vec<_> = all_commands().map(|cmd| cmd.name()).collect();
+    for (i, name) in names.iter().enumerate() {
+        assert!(!names[i + 1..].contains(name), "duplicate name {name:?}");
+    }
+}
+
+#[test]
+fn text_form_round_trips() {
+    for cmd in all_commands() {
+        assert_eq!(Command::from_text(cmd.cmd().to_str().unwrap()), Some(cmd));
+    }
+}
+
+#[test]
+fn text_form_is_case_insensitive() {
+    assert_eq!(Command::from_text("R"), Some(Command::ReadAvailableData));
+    assert_eq!(
+        Command::from_text("  B 4 "),
+        Some(Command::BaudRate(BaudRate::Baud19200))
+    );
+}
+
+#[test]
+fn text_form_rejects_unknown_commands() {
+    for text in ["", "z", "rr", "b", "b 7", "b x", "a 1"] {
+        assert_eq!(Command::from_text(text), None, "{text:?}");
+    }
+}
+
+#[test]
+fn letter_starts_the_encoding() {
+    for cmd in all_commands() {
+        assert!(cmd.cmd().to_str().unwrap().starts_with(cmd.letter()));
+    }
+}
+
+#[test]
+fn recognizes_its_answer() {
+    for cmd in all_commands() {
+        let mut sensor = SimulatedSensor::new(Scenario::Drizzle);
+        let mut out = String::new();
+        sensor.receive(cmd.cmd().to_str().unwrap(), &mut out);
+        let answered = out
+            .lines()
+            .filter_map(|line| Response::parse(line).ok())
+            .any(|response| cmd.is_answered_by(&response));
+        assert!(answered, "{cmd:?} got {out:?}");
+    }
+}
+
+#[test]
+fn ignores_answers_to_other_commands() {
+    let echo = Response::Echo('m');
+    assert!(Command::ForceMetric.is_answered_by(&echo));
+    assert!(!Command::ForceImperial.is_answered_by(&echo));
+    assert!(!Command::ReadAvailableData.is_answered_by(&Response::Echo('r')));
+    assert!(
+        !Command::BaudRate(BaudRate::Baud9600).is_answered_by(&Response::Baud(BaudRate::Baud19200))
+    );
+}
+
+#[test]
+fn asks_before_destructive_commands() {
+    assert!(Command::ResetAccumulationCounter.requires_confirmation());
+    assert!(Command::Kill.requires_confirmation());
+    assert!(Command::ForceMetric.requires_confirmation());
+    assert!(!Command::ReadAvailableData.requires_confirmation());
+    assert!(!Command::AccumulationData.requires_confirmation());
+}
@@ -0,0 +1,135 @@
+use rg15_protocol::{
+    BaudRate, Command, Fixed, PollTracker, Response, Scenario, SensorConfig, SimulatedSensor,
+    UnitSystem, BANNER,
+};
+
+/// Sends `cmd` to `sensor` and lets `config` observe every answer.
+fn send(sensor: &mut SimulatedSensor, config: &mut SensorConfig, cmd: Command) {
+    let mut out = String::new();
+    sensor.receive(cmd.cmd().to_str().unwrap(), &mut out);
+    for line in out.lines() {
+        config.observe(&Response::parse(line).unwrap());
+    }
+}
+
+#[test]
+fn starts_out_unknown() {
+    let config = SensorConfig::default();
+    assert_eq!(config.baud_rate, None);
+    assert_eq!(config.polling, None);
+    assert_eq!(config.units, None);
+}
+
+#[test]
+fn reads_firmware_from_banner() {
+    let mut config = SensorConfig::default();
+    config.observe(&Response::parse(BANNER).unwrap());
+    assert_eq!(config.firmware, Some(Fixed::from_thousandths(1000, 3)));
+}
+
+#[test]
+fn learns_from_acknowledgements() {
+    let mut sensor = SimulatedSensor::new(Scenario::Dry);
+    let mut config = SensorConfig::default();
+
+    send(&mut sensor, &mut config, Command::ContinousMode);
+    assert_eq!(config.polling, Some(false));
+    send(&mut sensor, &mut config, Command::ForceImperial);
+    assert_eq!(config.units, Some(UnitSystem::Imperial));
+    send(&mut sensor, &mut config, Command::ForceLowResolution);
+    assert_eq!(config.high_resolution, Some(false));
+    send(&mut sensor, &mut config, Command::EnableExternalTbInput);
+    assert_eq!(config.external_tb, Some(true));
+    send(
+        &mut sensor,
+        &mut config,
+        Command::BaudRate(BaudRate::Baud19200),
+    );
+    assert_eq!(config.baud_rate, Some(BaudRate::Baud19200));
+
+    send(&mut sensor, &mut config, Command::UseSwitchValue);
+    assert_eq!(config.units, None);
+    assert_eq!(config.high_resolution, None);
+}
+
+#[test]
+fn learns_units_and_total_from_readings() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    let mut config = SensorConfig::default();
+    let mut out = String::new();
+    sensor.advance(60 * 60 * 1000, &mut out);
+
+    send(&mut sensor, &mut config, Command::ReadAvailableData);
+    assert_eq!(config.units, Some(UnitSystem::Metric));
+    let total_acc = config.total_acc.unwrap();
+    assert!(total_acc.value.thousandths() > 0);
+
+    send(&mut sensor, &mut config, Command::ResetAccumulationCounter);
+    assert_eq!(config.total_acc.unwrap().value.thousandths(), 0);
+    assert_eq!(config.total_acc.unwrap().unit, total_acc.unit);
+}
+
+/// Sends `cmd` to `sensor` and lets `config` observe every answer with `polls`.
+fn poll(
+    sensor: &mut SimulatedSensor,
+    config: &mut SensorConfig,
+    polls: &PollTracker,
+    cmd: Command,
+) {
+    polls.sent(cmd);
+    let mut out = String::new();
+    sensor.receive(cmd.cmd().to_str().unwrap(), &mut out);
+    for line in out.lines() {
+        config.observe_unpolled(&Response::parse(line).unwrap(), polls);
+    }
+}
+
+#[test]
+fn polled_readings_keep_mode_unknown() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    let mut config = SensorConfig::default();
+    let polls = PollTracker::new();
+    let mut out = String::new();
+    sensor.advance(60 * 60 * 1000, &mut out);
+
+    poll(&mut sensor, &mut config, &polls, Command::ReadAvailableData);
+    poll(&mut sensor, &mut config, &polls, Command::AccumulationData);
+    assert_eq!(config.polling, None);
+}
+
+#[test]
+fn infers_continuous_mode_from_unpolled_readings() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    let mut config = SensorConfig::default();
+    let polls = PollTracker::new();
+    // switched before we listened, so the acknowledgement is missed
+    sensor.receive(
+        Command::ContinousMode.cmd().to_str().unwrap(),
+        &mut String::new(),
+    );
+
+    let mut out = String::new();
+    sensor.advance(60 * 60 * 1000, &mut out);
+    assert!(!out.is_empty());
+    for line in out.lines() {
+        config.observe_unpolled(&Response::parse(line).unwrap(), &polls);
+    }
+    assert_eq!(config.polling, Some(false));
+}
+
+#[test]
+fn counts_only_polls() {
+    let polls = PollTracker::new();
+    let reading = Response::parse("Acc 0.01 mm").unwrap();
+
+    polls.sent(Command::Kill);
+    polls.sent(Command::ForceMetric);
+    assert!(polls.is_unpolled(&reading));
+
+    polls.sent(Command::ReadAvailableData);
+    polls.sent(Command::AccumulationData);
+    assert!(!polls.is_unpolled(&Response::Echo('r')));
+    assert!(!polls.is_unpolled(&reading));
+    assert!(!polls.is_unpolled(&reading));
+    assert!(polls.is_unpolled(&reading));
+}
@@ -0,0 +1,171 @@
+use rg15_protocol::{Fixed, Measurement, ParseError, Reading, Unit, UnitSystem};
+
+fn measurement(thousandths: u32, decimals: u8, unit: Unit) -> Option<Measurement> {
+    Some(Measurement {
+        value: Fixed::from_thousandths(thousandths, decimals),
+        unit,
+    })
+}
+
+#[test]
+fn parses_full_metric_line() {
+    let reading =
+        Reading::parse("Acc  0.01 mm, EventAcc  1.25 mm, TotalAcc 12.50 mm, RInt  3.40 mmph\r\n")
+            .unwrap();
+    assert_eq!(reading.acc, measurement(10, 2, Unit::Millimeters));
+    assert_eq!(reading.event_acc, measurement(1250, 2, Unit::Millimeters));
+    assert_eq!(reading.total_acc, measurement(12500, 2, Unit::Millimeters));
+    assert_eq!(
+        reading.r_int,
+        measurement(3400, 2, Unit::MillimetersPerHour)
+    );
+    assert_eq!(reading.unit_system(), Some(UnitSystem::Metric));
+}
+
+#[test]
+fn parses_full_imperial_line() {
+    let reading =
+        Reading::parse("Acc 0.001 in, EventAcc 0.012 in, TotalAcc 1.234 in, RInt 0.100 iph")
+            .unwrap();
+    assert_eq!(reading.acc, measurement(1, 3, Unit::Inches));
+    assert_eq!(reading.total_acc, measurement(1234, 3, Unit::Inches));
+    assert_eq!(reading.r_int, measurement(100, 3, Unit::InchesPerHour));
+    assert_eq!(reading.unit_system(), Some(UnitSystem::Imperial));
+}
+
+#[test]
+fn parses_accumulation_only_line() {
+    let reading = Reading::parse("Acc  0.20 mm").unwrap();
+    assert_eq!(reading.acc, measurement(200, 2, Unit::Millimeters));
+    assert_eq!(reading.event_acc, None);
+    assert_eq!(reading.total_acc, None);
+    assert_eq!(reading.r_int, None);
+}
+
+#[test]
+fn rejects_empty_lines() {
+    assert_eq!(Reading::parse(""), Err(ParseError::Empty));
+    assert_eq!(Reading::parse(" \r\n"), Err(ParseError::Empty));
+}
+
+#[test]
+fn rejects_partial_lines() {
+    assert_eq!(
+        Reading::parse("Acc  0.01 mm, EventAcc"),
+        Err(ParseError::MissingValue)
+    );
+    assert_eq!(
+        Reading::parse("Acc  0.01 mm, EventAcc 0.02"),
+        Err(ParseError::MissingUnit)
+    );
+    assert_eq!(
+        Reading::parse("Acc  0.01 mm,"),
+        Err(ParseError::MissingValue)
+    );
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    assert_eq!(Reading::parse("Foo 0.01 mm"), Err(ParseError::UnknownField));
+    assert_eq!(
+        Reading::parse("Acc 0.0x mm"),
+        Err(ParseError::InvalidNumber)
+    );
+    assert_eq!(Reading::parse("Acc 0.01 cm"), Err(ParseError::InvalidUnit));
+    assert_eq!(
+        Reading::parse("Acc 0.01 mm mm"),
+        Err(ParseError::InvalidUnit)
+    );
+    assert_eq!(
+        Reading::parse("Acc 0.01 mm, Acc 0.02 mm"),
+        Err(ParseError::DuplicateField)
+    );
+}
+
+#[test]
+fn rejects_mismatched_units() {
+    assert_eq!(
+        Reading::parse("Acc 0.01 mmph"),
+        Err(ParseError::UnitMismatch)
+    );
+    assert_eq!(
+        Reading::parse("RInt 0.01 in"),
+        Err(ParseError::UnitMismatch)
+    );
+}
+
+#[test]
+fn merge_keeps_missing_fields() {
+    let mut reading = Reading::parse("Acc 0.01 mm, TotalAcc 5.00 mm").unwrap();
+    reading.merge(Reading::parse("Acc 0.02 mm").unwrap());
+    assert_eq!(reading.acc, measurement(20, 2, Unit::Millimeters));
+    assert_eq!(reading.total_acc, measurement(5000, 2, Unit::Millimeters));
+}
+
+#[test]
+fn fixed_parses_and_displays() {
+    for (text, thousandths) in [
+        ("0", 0),
+        ("12", 12000),
+        ("0.2", 200),
+        ("1.05", 1050),
+        ("0.001", 1),
+    ] {
+        let fixed = Fixed::parse(text).unwrap();
+        assert_eq!(fixed.thousandths(), thousandths);
+        assert_eq!(fixed.to_string(), text);
+    }
+}
+
+#[test]
+fn fixed_rejects_invalid_numbers() {
+    for text in [
+        "",
+        ".5",
+        "1.0001",
+        "-1",
+        "1.-2",
+        "1e3",
+        "99999999",
+        "4294967.999",
+    ] {
+        assert_eq!(Fixed::parse(text), Err(ParseError::InvalidNumber), "{text}");
+    }
+}
+
+#[test]
+fn measurement_displays_with_unit() {
+    let reading = Reading::parse("RInt 12.34 mmph").unwrap();
+    assert_eq!(reading.r_int.unwrap().to_string(), "12.34 mmph");
+}
+
+#[test]
+fn displays_like_the_sensor() {
+    let line = "Acc 0.01 mm, EventAcc 1.25 mm, TotalAcc 12.50 mm, RInt 3.40 mmph";
+    assert_eq!(Reading::parse(line).unwrap().to_string(), line);
+    assert_eq!(
+        Reading::parse("TotalAcc 0.100 in").unwrap().to_string(),
+        "TotalAcc 0.100 in"
+    );
+    assert_eq!(Reading::default().to_string(), "");
+}
+
+#[test]
+fn converts_between_unit_systems() {
+    let mm = Measurement {
+        value: Fixed::from_thousandths(25400, 2),
+        unit: Unit::Millimeters,
+    };
+    let inches = mm.to_system(UnitSystem::Imperial);
+    assert_eq!(inches.to_string(), "1.000 in");
+    assert_eq!(inches.to_system(UnitSystem::Metric), mm);
+    assert_eq!(mm.to_system(UnitSystem::Metric), mm);
+
+    let rate = measurement(3400, 2, Unit::MillimetersPerHour).unwrap();
+    assert_eq!(
+        rate.to_system(UnitSystem::Imperial).to_string(),
+        "0.134 iph"
+    );
+    let rate = measurement(1, 3, Unit::InchesPerHour).unwrap();
+    assert_eq!(rate.to_system(UnitSystem::Metric).to_string(), "0.03 mmph");
+}
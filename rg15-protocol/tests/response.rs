@@ -0,0 +1,44 @@
+use rg15_protocol::{BaudRate, ParseError, Response};
+
+#[test]
+fn classifies_readings() {
+    let response = Response::parse("Acc  0.01 mm\r\n").unwrap();
+    assert!(matches!(response, Response::Reading(reading) if reading.acc.is_some()));
+}
+
+#[test]
+fn reports_broken_readings() {
+    assert_eq!(
+        Response::parse("Acc  0.01 mm, EventAcc"),
+        Err(ParseError::MissingValue)
+    );
+}
+
+#[test]
+fn classifies_echoes() {
+    assert_eq!(Response::parse("p\r\n"), Ok(Response::Echo('p')));
+    assert_eq!(Response::parse("M"), Ok(Response::Echo('m')));
+}
+
+#[test]
+fn classifies_baud_acknowledgements() {
+    assert_eq!(
+        Response::parse("Baud 19200"),
+        Ok(Response::Baud(BaudRate::Baud19200))
+    );
+    assert_eq!(Response::parse("Baud 300"), Err(ParseError::InvalidNumber));
+    assert_eq!(Response::parse("Baud 3"), Err(ParseError::InvalidNumber));
+}
+
+#[test]
+fn keeps_other_lines() {
+    assert_eq!(
+        Response::parse("RG-15 v1.000\r\n"),
+        Ok(Response::Other("RG-15 v1.000"))
+    );
+}
+
+#[test]
+fn rejects_empty_lines() {
+    assert_eq!(Response::parse("\r\n"), Err(ParseError::Empty));
+}
@@ -0,0 +1,61 @@
+use rg15_protocol::{BaudRate, Command, ScriptError, ScriptStep};
+
+#[test]
+fn parses_commands() {
+    assert_eq!(
+        ScriptStep::parse("r"),
+        Ok(Some(ScriptStep::Send(Command::ReadAvailableData)))
+    );
+    assert_eq!(
+        ScriptStep::parse("  b 4\r"),
+        Ok(Some(ScriptStep::Send(Command::BaudRate(
+            BaudRate::Baud19200
+        ))))
+    );
+}
+
+#[test]
+fn parses_directives() {
+    assert_eq!(
+        ScriptStep::parse("wait 500ms"),
+        Ok(Some(ScriptStep::Wait(500)))
+    );
+    assert_eq!(
+        ScriptStep::parse("wait 2s"),
+        Ok(Some(ScriptStep::Wait(2000)))
+    );
+    assert_eq!(
+        ScriptStep::parse("expect Acc"),
+        Ok(Some(ScriptStep::Expect("Acc")))
+    );
+    assert_eq!(
+        ScriptStep::parse("log after reset"),
+        Ok(Some(ScriptStep::Log("after reset")))
+    );
+}
+
+#[test]
+fn skips_empty_lines_and_comments() {
+    assert_eq!(ScriptStep::parse(""), Ok(None));
+    assert_eq!(ScriptStep::parse("   "), Ok(None));
+    assert_eq!(ScriptStep::parse("# poll once"), Ok(None));
+}
+
+#[test]
+fn rejects_invalid_lines() {
+    assert_eq!(ScriptStep::parse("z"), Err(ScriptError::UnknownCommand));
+    assert_eq!(ScriptStep::parse("wait"), Err(ScriptError::InvalidDuration));
+    assert_eq!(
+        ScriptStep::parse("wait 5"),
+        Err(ScriptError::InvalidDuration)
+    );
+    assert_eq!(
+        ScriptStep::parse("wait xms"),
+        Err(ScriptError::InvalidDuration)
+    );
+    assert_eq!(
+        ScriptStep::parse("wait 5000000s"),
+        Err(ScriptError::InvalidDuration)
+    );
+    assert_eq!(ScriptStep::parse("expect"), Err(ScriptError::MissingText));
+}
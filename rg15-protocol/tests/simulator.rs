@@ -0,0 +1,149 @@
+use rg15_protocol::{
+    BaudRate, Command, Reading, Response, Scenario, SimulatedSensor, UnitSystem, BANNER,
+};
+
+const MINUTE: u32 = 60 * 1000;
+
+fn send(sensor: &mut SimulatedSensor, cmd: Command) -> String {
+    let mut out = String::new();
+    sensor.receive(cmd.cmd().to_str().unwrap(), &mut out);
+    out
+}
+
+fn reading(out: &str) -> Reading {
+    match Response::parse(out) {
+        Ok(Response::Reading(reading)) => reading,
+        other => panic!("expected reading, got {other:?}"),
+    }
+}
+
+#[test]
+fn answers_every_command() {
+    for cmd in Command::list() {
+        let mut sensor = SimulatedSensor::new(Scenario::Drizzle);
+        let out = send(&mut sensor, *cmd);
+        assert!(out.ends_with("\r\n"), "{cmd:?} got {out:?}");
+        for line in out.lines() {
+            assert!(Response::parse(line).is_ok(), "{cmd:?} sent {line:?}");
+        }
+    }
+}
+
+#[test]
+fn echoes_setting_commands() {
+    let mut sensor = SimulatedSensor::new(Scenario::Dry);
+    assert_eq!(send(&mut sensor, Command::ForceImperial), "i\r\n");
+    assert_eq!(sensor.units, UnitSystem::Imperial);
+    assert_eq!(send(&mut sensor, Command::ContinousMode), "c\r\n");
+    assert!(!sensor.polling);
+    assert_eq!(send(&mut sensor, Command::ForceLowResolution), "l\r\n");
+    assert!(!sensor.high_resolution);
+    assert_eq!(send(&mut sensor, Command::UseSwitchValue), "s\r\n");
+    assert_eq!(sensor.units, UnitSystem::Metric);
+    assert!(sensor.high_resolution);
+}
+
+#[test]
+fn acknowledges_baud_rate_before_switching() {
+    let mut sensor = SimulatedSensor::new(Scenario::Dry);
+    let out = send(&mut sensor, Command::BaudRate(BaudRate::Baud57600));
+    assert_eq!(
+        Response::parse(&out),
+        Ok(Response::Baud(BaudRate::Baud57600))
+    );
+    assert_eq!(sensor.baud_rate, BaudRate::Baud57600);
+}
+
+#[test]
+fn kill_sends_banner() {
+    let mut sensor = SimulatedSensor::new(Scenario::Dry);
+    let out = send(&mut sensor, Command::Kill);
+    assert_eq!(out.lines().next(), Some(BANNER));
+}
+
+#[test]
+fn ignores_unknown_commands() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    let mut out = String::new();
+    sensor.receive("z\r\n", &mut out);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn dry_scenario_stays_at_zero() {
+    let mut sensor = SimulatedSensor::new(Scenario::Dry);
+    let mut out = String::new();
+    sensor.advance(60 * MINUTE, &mut out);
+    let reading = reading(&send(&mut sensor, Command::ReadAvailableData));
+    assert_eq!(reading.total_acc.unwrap().value.thousandths(), 0);
+    assert_eq!(reading.r_int.unwrap().value.thousandths(), 0);
+}
+
+#[test]
+fn rain_accumulates_and_acc_resets_after_report() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    let mut out = String::new();
+    for _ in 0..10 {
+        sensor.advance(MINUTE, &mut out);
+    }
+    assert!(out.is_empty(), "polling mode sends nothing on its own");
+
+    let first = reading(&send(&mut sensor, Command::ReadAvailableData));
+    let acc = first.acc.unwrap().value.thousandths();
+    assert!(acc > 0);
+    assert_eq!(first.total_acc.unwrap().value.thousandths(), acc);
+
+    let second = reading(&send(&mut sensor, Command::AccumulationData));
+    assert!(second.acc.unwrap().value.thousandths() < 10);
+    assert!(second.total_acc.is_none());
+}
+
+#[test]
+fn reset_clears_total_accumulation() {
+    let mut sensor = SimulatedSensor::new(Scenario::Drizzle);
+    let mut out = String::new();
+    sensor.advance(60 * MINUTE, &mut out);
+    assert_eq!(
+        send(&mut sensor, Command::ResetAccumulationCounter),
+        "o\r\n"
+    );
+    let reading = reading(&send(&mut sensor, Command::ReadAvailableData));
+    assert_eq!(reading.total_acc.unwrap().value.thousandths(), 0);
+}
+
+#[test]
+fn continuous_mode_reports_on_its_own() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    send(&mut sensor, Command::ContinousMode);
+    let mut out = String::new();
+    for _ in 0..5 {
+        sensor.advance(MINUTE, &mut out);
+    }
+    assert!(out.lines().count() > 0);
+    for line in out.lines() {
+        assert!(reading(line).r_int.is_some());
+    }
+}
+
+#[test]
+fn imperial_reports_inches() {
+    let mut sensor = SimulatedSensor::new(Scenario::Storm);
+    send(&mut sensor, Command::ForceImperial);
+    let mut out = String::new();
+    sensor.advance(30 * MINUTE, &mut out);
+    let reading = reading(&send(&mut sensor, Command::ReadAvailableData));
+    assert_eq!(reading.unit_system(), Some(UnitSystem::Imperial));
+    assert!(reading.total_acc.unwrap().value.thousandths() > 0);
+}
+
+#[test]
+fn scenarios_stay_in_plausible_range() {
+    for scenario in Scenario::list() {
+        for minute in 0..(3 * 60) {
+            // at most 100 mm/h
+            assert!(scenario.intensity(minute * MINUTE) <= 100_000);
+        }
+    }
+    assert!(Scenario::Shower.intensity(15 * MINUTE) == 0);
+    assert!(Scenario::Shower.intensity(5 * MINUTE) > 0);
+}
@@ -0,0 +1,116 @@
+use core::fmt::Write;
+
+use flipperzero::furi::{string::FuriString, time::Instant};
+use rg15_protocol::{BaudRate, Command, Response};
+
+use crate::{
+    line_reader::LineReader,
+    responses::response_timeout,
+    safe::{serial_marker::Initialized, *},
+    SCREEN_WIDTH,
+};
+
+const AUTO_BAUD_VIEW_ID: u32 = 0;
+
+#[derive(Debug, Default)]
+struct Progress {
+    baud_rate: Option<BaudRate>,
+    step: usize,
+}
+
+/// Goes through all [`BaudRate`]s and returns the first one at which the sensor
+/// answers a poll with a valid reading.
+///
+/// The serial handle is left at the detected rate, or at the last tried one if
+/// none worked.
+pub fn detect(
+    serial_handle: &SerialHandle<Initialized>,
+    lines: &mut LineReader,
+) -> Option<BaudRate> {
+    let gui = Gui::open();
+    let mut view_dispatcher = ViewDispatcher::new(gui, ViewDispatcherType::Fullscreen);
+
+    // the gui thread draws the view on every model commit, so the dispatcher
+    // doesn't need to run while we block here
+    let mut view = View::<(), Progress>::new();
+    view.set_draw_callback::<ProgressView>();
+    let view = view_dispatcher.add_view(view, AUTO_BAUD_VIEW_ID);
+    view_dispatcher.switch_to_view(AUTO_BAUD_VIEW_ID);
+
+    for (step, baud_rate) in BaudRate::list().into_iter().enumerate() {
+        if let Some(view) = view.upgrade() {
+            let progress = view.model();
+            progress.model.baud_rate = Some(baud_rate);
+            progress.model.step = step;
+        }
+
+        if probe(serial_handle, lines, baud_rate) {
+            return Some(baud_rate);
+        }
+    }
+
+    None
+}
+
+/// Switches to `baud_rate` and returns whether the sensor answers a poll with a
+/// valid reading.
+pub fn probe(
+    serial_handle: &SerialHandle<Initialized>,
+    lines: &mut LineReader,
+    baud_rate: BaudRate,
+) -> bool {
+    serial_handle.set_br(baud_rate.rate() as u32);
+
+    // drop whatever arrived at the previous rate
+    lines.clear();
+
+    serial_handle.tx(Command::ReadAvailableData.cmd().as_bytes());
+
+    let timeout = response_timeout(baud_rate);
+    let start = Instant::now();
+
+    // garbage at a wrong rate is hardly ever valid utf-8, let alone a reading
+    while let Some(line) = lines.next_line_until(start, timeout) {
+        let Ok(line) = line else { continue };
+        if let Ok(Response::Reading(_)) = Response::parse(line) {
+            return true;
+        }
+    }
+
+    false
+}
+
+struct ProgressView;
+
+impl ViewDrawCallback for ProgressView {
+    type Model = Progress;
+
+    fn callback(canvas: &mut Canvas, progress: &Self::Model) {
+        canvas.draw_str(10, 14, c"Detecting baud rate...");
+
+        let Some(baud_rate) = progress.baud_rate else {
+            return;
+        };
+
+        let total = BaudRate::list().len();
+        let mut text = FuriString::new();
+        write!(
+            text,
+            "Trying {} ({}/{})",
+            baud_rate.rate(),
+            progress.step + 1,
+            total
+        )
+        .expect("infallible");
+        canvas.draw_str(10, 32, text.as_c_str());
+
+        let padding = 10;
+        canvas.elements_progress_bar(
+            padding,
+            44,
+            SCREEN_WIDTH as usize - 2 * padding as usize,
+            // counts the rate being tried, so the last one fills the bar
+            (progress.step + 1) as f32 / total as f32,
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use rg15_protocol::{BaudRate, Command, Response};
+
+use crate::{
+    responses::{response_timeout, Responses},
+    transport::Transport,
+};
+
+/// Number of polls sent at the new rate before giving up on it.
+const VERIFY_ATTEMPTS: usize = 3;
+
+/// Outcome of [`change_baud_rate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaudChange {
+    /// The sensor talks at the new rate.
+    Changed,
+    /// The sensor didn't take the new rate and still talks at the previous
+    /// one.
+    Reverted,
+    /// The sensor answers at neither rate, only auto-baud can find it again.
+    Lost,
+}
+
+/// Switches the sensor and the transport to `baud_rate`.
+///
+/// The new rate is only kept if the sensor acknowledged exactly that rate and
+/// answers a poll at it, otherwise both sides are switched back to the
+/// previous rate so that a deployed gauge stays reachable.
+pub fn change_baud_rate(
+    transport: &Transport,
+    responses: &Responses,
+    baud_rate: BaudRate,
+) -> BaudChange {
+    let previous = BaudRate::try_from(transport.baud_rate() as u16).unwrap_or_default();
+
+    // the acknowledgement is still sent at the previous rate, `tx` already
+    // waits until our command left the wire
+    let since = responses.seq();
+    transport.tx(Command::BaudRate(baud_rate).cmd().as_bytes());
+    let acknowledged = responses
+        .wait_for(since, response_timeout(previous), |line| {
+            matches!(Response::parse(line), Ok(Response::Baud(_)))
+        })
+        .is_some_and(|line| Response::parse(&line) == Ok(Response::Baud(baud_rate)));
+
+    transport.set_br(baud_rate.rate() as u32);
+    if acknowledged && verify(transport, responses, baud_rate) {
+        return BaudChange::Changed;
+    }
+
+    // the sensor may have switched without answering, so send it back too
+    transport.tx(Command::BaudRate(previous).cmd().as_bytes());
+    transport.set_br(previous.rate() as u32);
+    match verify(transport, responses, previous) {
+        true => BaudChange::Reverted,
+        false => BaudChange::Lost,
+    }
+}
+
+fn verify(transport: &Transport, responses: &Responses, baud_rate: BaudRate) -> bool {
+    (0..VERIFY_ATTEMPTS).any(|_| {
+        let since = responses.seq();
+        transport.tx(Command::ReadAvailableData.cmd().as_bytes());
+        responses
+            .wait_for(since, response_timeout(baud_rate), |line| {
+                matches!(Response::parse(line), Ok(Response::Reading(_)))
+            })
+            .is_some()
+    })
+}
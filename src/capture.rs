@@ -0,0 +1,234 @@
+//! Raw serial captures on the SD card.
+//!
+//! A capture is a text file starting with a header line
+//! `# rg-15 capture <rtc timestamp> <baud rate>`, followed by one record per
+//! line: the milliseconds since the capture started and the received bytes
+//! as hex, e.g. `1532 4163632020302e3031206d6d0d0a`.
+
+use core::{
+    cmp,
+    ffi::CStr,
+    fmt::Write,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use alloc::{ffi::CString, format, string::String, sync::Arc, vec::Vec};
+use flipperzero::furi::{
+    self,
+    stream_buffer::{Receiver, Sender, StreamBuffer},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crate::{logger::DATA_DIR, safe::*};
+
+pub const CAPTURE_DIR: &CStr = c"/ext/apps_data/rg15/captures";
+pub const CAPTURE_EXTENSION: &CStr = c".cap";
+
+const HEADER_PREFIX: &str = "# rg-15 capture";
+
+/// Received bytes are grouped into one record until a line ends or this many
+/// bytes arrived.
+const MAX_RECORD_LEN: usize = 32;
+
+/// Writes everything the sensor sends into a new capture file.
+pub struct Capture {
+    file: File,
+    start: Instant,
+    record: Vec<u8>,
+    record_time: u32,
+}
+
+impl Capture {
+    pub fn create(baud_rate: u32) -> Option<Self> {
+        let storage = Storage::open();
+        if !storage.mkdir(DATA_DIR) || !storage.mkdir(CAPTURE_DIR) {
+            return None;
+        }
+
+        let now = DateTime::now();
+        let path = format!(
+            "{}/{:04}-{:02}-{:02}_{:02}-{:02}-{:02}.cap",
+            CAPTURE_DIR.to_str().expect("valid utf-8"),
+            now.year,
+            now.month,
+            now.day,
+            now.hour,
+            now.minute,
+            now.second
+        );
+        let path = CString::new(path).expect("no nul bytes in path");
+        let mut file = File::open(&path, FileAccess::Write, FileOpen::CreateAlways)?;
+        let header = format!("{HEADER_PREFIX} {now} {baud_rate}\n");
+        file.write(header.as_bytes());
+
+        Some(Self {
+            file,
+            start: Instant::now(),
+            record: Vec::with_capacity(MAX_RECORD_LEN),
+            record_time: 0,
+        })
+    }
+
+    pub fn record(&mut self, bytes: &[u8]) {
+        if self.record.is_empty() {
+            self.record_time = self.start.elapsed().as_millis() as u32;
+        }
+
+        self.record.extend_from_slice(bytes);
+        if self.record.len() >= MAX_RECORD_LEN || bytes.contains(&b'\n') {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.record.is_empty() {
+            return;
+        }
+
+        let mut line = String::new();
+        write!(line, "{}", self.record_time).expect("infallible");
+        line.push(' ');
+        for byte in self.record.iter() {
+            write!(line, "{byte:02x}").expect("infallible");
+        }
+        line.push('\n');
+
+        self.file.write(line.as_bytes());
+        self.record.clear();
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    Unreadable,
+    /// The first line isn't a capture header with the baud rate.
+    InvalidHeader,
+}
+
+/// Feeds a capture file with its original timing into a stream buffer, just
+/// like the serial interrupt does.
+pub struct Replay {
+    baud_rate: u32,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle>,
+}
+
+impl Replay {
+    pub fn start(path: &CStr) -> Result<(Self, Receiver), ReplayError> {
+        let mut lines = File::open(path, FileAccess::Read, FileOpen::Existing)
+            .ok_or(ReplayError::Unreadable)?
+            .lines();
+
+        let mut header = String::new();
+        if !lines.next_line(&mut header) || !header.starts_with(HEADER_PREFIX) {
+            return Err(ReplayError::InvalidHeader);
+        }
+        let baud_rate = header
+            .split_whitespace()
+            .last()
+            .and_then(|baud_rate| baud_rate.parse().ok())
+            .ok_or(ReplayError::InvalidHeader)?;
+
+        let size = NonZeroUsize::new(4096).expect("non-zero value");
+        let (tx, rx) = StreamBuffer::new(size, 1).into_stream();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = furi::thread::Builder::new().stack_size(2048).spawn({
+            let stop = stop.clone();
+            move || {
+                replay(lines, &tx, &stop);
+
+                // the reader blocks until data arrives, so wake it up once the
+                // replay is stopped to let it notice
+                while !stop.load(Ordering::Relaxed) {
+                    furi::thread::sleep(Duration::from_millis(100));
+                }
+                tx.send(b"\n");
+                0
+            }
+        });
+
+        let replay = Self {
+            baud_rate,
+            stop,
+            thread: Some(thread),
+        };
+        Ok((replay, rx))
+    }
+
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join();
+        }
+    }
+}
+
+fn replay(mut lines: FileLines, tx: &Sender, stop: &AtomicBool) {
+    let start = Instant::now();
+    let mut line = String::new();
+    let mut bytes = Vec::new();
+
+    while lines.next_line(&mut line) {
+        let Some(time) = parse_record(&line, &mut bytes) else {
+            continue;
+        };
+
+        // sleep in slices to notice a stop during long pauses in the capture
+        loop {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let elapsed = start.elapsed().as_millis() as u32;
+            if elapsed >= time {
+                break;
+            }
+            let wait = cmp::min(time - elapsed, 100);
+            furi::thread::sleep(Duration::from_millis(wait));
+        }
+
+        let mut rest = bytes.as_slice();
+        while !rest.is_empty() {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let sent = tx.send(rest);
+            rest = &rest[sent..];
+            if sent == 0 {
+                furi::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+}
+
+/// Parses a record line into its time and bytes, `None` for malformed lines.
+fn parse_record(line: &str, bytes: &mut Vec<u8>) -> Option<u32> {
+    let (time, hex) = line.split_once(' ')?;
+    let time = time.parse().ok()?;
+
+    bytes.clear();
+    let hex = hex.trim().as_bytes();
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    for pair in hex.chunks(2) {
+        let pair = core::str::from_utf8(pair).ok()?;
+        bytes.push(u8::from_str_radix(pair, 16).ok()?);
+    }
+
+    Some(time)
+}
@@ -0,0 +1,101 @@
+use alloc::{format, string::String};
+use flipperzero::furi::time::Duration;
+use rg15_protocol::{BaudRate, Command, Response};
+
+use crate::{
+    baud_change::{self, BaudChange},
+    responses::{response_timeout, Responses},
+    transport::Transport,
+};
+
+/// Time in ms the sensor needs to restart and send its banner after a
+/// [`Command::Kill`].
+const RESTART_TIMEOUT: u32 = 2000;
+
+/// What came of a command sent in a [`CommandSession`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandResult {
+    /// The sensor answered with this line.
+    Answered(String),
+    /// Nothing answering the command arrived in time.
+    NoAnswer,
+    /// A baud rate change failed and the sensor doesn't answer at the
+    /// previous rate either.
+    SensorLost,
+}
+
+impl CommandResult {
+    pub fn is_answered(&self) -> bool {
+        matches!(self, CommandResult::Answered(_))
+    }
+}
+
+/// Sends commands and waits until the sensor answered them.
+pub struct CommandSession<'t> {
+    transport: &'t Transport,
+    responses: &'t Responses,
+}
+
+impl<'t> CommandSession<'t> {
+    pub fn new(transport: &'t Transport, responses: &'t Responses) -> Self {
+        Self {
+            transport,
+            responses,
+        }
+    }
+
+    /// Sends `cmd` and blocks until the line answering it arrived or the
+    /// sensor took too long.
+    ///
+    /// [`Command::BaudRate`] also switches the transport, see
+    /// [`baud_change::change_baud_rate`].
+    pub fn send(&self, cmd: Command) -> CommandResult {
+        if let Command::BaudRate(baud_rate) = cmd {
+            return match baud_change::change_baud_rate(self.transport, self.responses, baud_rate) {
+                BaudChange::Changed => {
+                    CommandResult::Answered(format!("Baud {}", baud_rate.rate()))
+                }
+                BaudChange::Reverted => CommandResult::NoAnswer,
+                BaudChange::Lost => CommandResult::SensorLost,
+            };
+        }
+
+        let since = self.responses.seq();
+        self.transport.tx(cmd.cmd().as_bytes());
+        self.wait_for_answer(since, Some(cmd))
+    }
+
+    /// Sends `text` as is with a line ending, for commands [`Command`]
+    /// doesn't know.
+    ///
+    /// Waits for the answer like [`CommandSession::send`] if the text is a
+    /// known command after all, otherwise takes whatever line the sensor
+    /// sends next.
+    pub fn send_text(&self, text: &str) -> CommandResult {
+        let since = self.responses.seq();
+        self.transport.tx(format!("{text}\r\n").as_bytes());
+        self.wait_for_answer(since, Command::from_text(text))
+    }
+
+    fn wait_for_answer(&self, since: u32, cmd: Option<Command>) -> CommandResult {
+        let timeout = match cmd {
+            Some(Command::Kill) => {
+                Duration::from_millis(RESTART_TIMEOUT + self.timeout().as_millis() as u32)
+            }
+            _ => self.timeout(),
+        };
+        self.responses
+            .wait_for(since, timeout, |line| match cmd {
+                Some(cmd) => {
+                    Response::parse(line).is_ok_and(|response| cmd.is_answered_by(&response))
+                }
+                None => true,
+            })
+            .map_or(CommandResult::NoAnswer, CommandResult::Answered)
+    }
+
+    fn timeout(&self) -> Duration {
+        let baud_rate = BaudRate::try_from(self.transport.baud_rate() as u16).unwrap_or_default();
+        response_timeout(baud_rate)
+    }
+}
@@ -0,0 +1,65 @@
+use core::{ffi::CStr, fmt::Write};
+
+use flipperzero::furi::string::FuriString;
+use rg15_protocol::{SensorConfig, UnitSystem};
+
+use crate::{safe::*, SCREEN_WIDTH};
+
+/// Shows what is known about the settings stored in the sensor.
+pub struct DeviceInfoView;
+
+impl ViewDrawCallback for DeviceInfoView {
+    type Model = SensorConfig;
+
+    fn callback(canvas: &mut Canvas, config: &Self::Model) {
+        let on_off = |on: Option<bool>, yes: &'static CStr, no: &'static CStr| {
+            on.map(|on| if on { yes } else { no })
+        };
+        let mut firmware = FuriString::new();
+        let mut baud_rate = FuriString::new();
+        let mut total_acc = FuriString::new();
+        if let Some(version) = config.firmware {
+            write!(firmware, "v{version}").expect("infallible");
+        }
+        if let Some(rate) = config.baud_rate {
+            write!(baud_rate, "{}", rate.rate()).expect("infallible");
+        }
+        if let Some(measurement) = config.total_acc {
+            write!(total_acc, "{measurement}").expect("infallible");
+        }
+        let units = config.units.map(|units| match units {
+            UnitSystem::Metric => c"metric",
+            UnitSystem::Imperial => c"imperial",
+        });
+
+        [
+            (c"firmware:", config.firmware.map(|_| firmware.as_c_str())),
+            (
+                c"baud rate:",
+                config.baud_rate.map(|_| baud_rate.as_c_str()),
+            ),
+            (
+                c"resolution:",
+                on_off(config.high_resolution, c"high", c"low"),
+            ),
+            (c"mode:", on_off(config.polling, c"polling", c"continuous")),
+            (c"units:", units),
+            (c"external TB:", on_off(config.external_tb, c"on", c"off")),
+            (
+                c"total acc:",
+                config.total_acc.map(|_| total_acc.as_c_str()),
+            ),
+        ]
+        .iter()
+        .enumerate()
+        .for_each(|(i, (label, value))| {
+            let padding = 4;
+            let y = (i as i32 + 1) * 9;
+            canvas.draw_str(padding, y, label);
+            // unknown until the sensor reported it
+            let value = value.unwrap_or(c"?");
+            let value_width = canvas.string_width(value) as u32;
+            canvas.draw_str((SCREEN_WIDTH - value_width) as i32 - padding, y, value);
+        });
+    }
+}
@@ -0,0 +1,85 @@
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use flipperzero::furi::string::FuriString;
+
+use crate::{safe::*, SCREEN_WIDTH};
+
+/// What happened to the lines the rx thread assembled.
+#[derive(Debug, Default)]
+pub struct LineStats {
+    received: AtomicU32,
+    invalid_utf8: AtomicU32,
+    too_long: AtomicU32,
+}
+
+impl LineStats {
+    pub fn count_received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count_invalid_utf8(&self) {
+        self.invalid_utf8.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count_too_long(&self) {
+        self.too_long.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of all counters for the [`DiagnosticsView`].
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    // `None` for sources without a serial port
+    frame_errors: Option<u32>,
+    noise_errors: Option<u32>,
+    overrun_errors: Option<u32>,
+    lines: u32,
+    invalid_utf8: u32,
+    too_long: u32,
+}
+
+impl Diagnostics {
+    pub fn update(&mut self, rx_errors: Option<&RxErrors>, line_stats: &LineStats) {
+        self.frame_errors = rx_errors.map(RxErrors::frame);
+        self.noise_errors = rx_errors.map(RxErrors::noise);
+        self.overrun_errors = rx_errors.map(RxErrors::overrun);
+        self.lines = line_stats.received.load(Ordering::Relaxed);
+        self.invalid_utf8 = line_stats.invalid_utf8.load(Ordering::Relaxed);
+        self.too_long = line_stats.too_long.load(Ordering::Relaxed);
+    }
+}
+
+pub struct DiagnosticsView;
+
+impl ViewDrawCallback for DiagnosticsView {
+    type Model = Diagnostics;
+
+    fn callback(canvas: &mut Canvas, diagnostics: &Self::Model) {
+        [
+            (c"frame errors:", diagnostics.frame_errors),
+            (c"noise errors:", diagnostics.noise_errors),
+            (c"overrun errors:", diagnostics.overrun_errors),
+            (c"lines received:", Some(diagnostics.lines)),
+            (c"invalid utf-8:", Some(diagnostics.invalid_utf8)),
+            (c"too long:", Some(diagnostics.too_long)),
+        ]
+        .iter()
+        .enumerate()
+        .for_each(|(i, (label, count))| {
+            let padding = 4;
+            let y = (i as i32 + 1) * 10;
+            canvas.draw_str(padding, y, label);
+            let mut value = FuriString::new();
+            match count {
+                Some(count) => write!(value, "{count}").expect("infallible"),
+                None => value.push_str("-"),
+            }
+            let value = value.as_c_str();
+            let value_width = canvas.string_width(value) as u32;
+            canvas.draw_str((SCREEN_WIDTH - value_width) as i32 - padding, y, value);
+        });
+    }
+}
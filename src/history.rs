@@ -0,0 +1,171 @@
+use core::fmt::Write;
+
+use flipperzero::furi::string::FuriString;
+use rg15_protocol::{Fixed, Measurement, Unit};
+
+use crate::safe::*;
+
+/// Number of samples kept, each is drawn as one bar.
+pub const HISTORY_LEN: usize = 50;
+
+const BAR_WIDTH: usize = 2;
+
+// plot area including its frame, leaves room for the labels left and below
+const PLOT_X: i32 = 26;
+const PLOT_Y: i32 = 0;
+const PLOT_WIDTH: usize = HISTORY_LEN * BAR_WIDTH + 2;
+const PLOT_HEIGHT: usize = 54;
+
+/// Minimum space in pixels between the labels below the plot.
+const AXIS_LABEL_GAP: i32 = 4;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Sample {
+    /// Seconds since the app started.
+    time: u32,
+    /// Rain intensity in thousandths of [`History::unit`].
+    intensity: u32,
+}
+
+/// Ring buffer of the latest rain intensities.
+#[derive(Debug)]
+pub struct History {
+    samples: [Sample; HISTORY_LEN],
+    // index the next sample is written to
+    next: usize,
+    len: usize,
+    unit: Option<Unit>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            samples: [Sample::default(); HISTORY_LEN],
+            next: 0,
+            len: 0,
+            unit: None,
+        }
+    }
+}
+
+impl History {
+    /// Adds the intensity measured `time` seconds after the app started.
+    ///
+    /// Samples in other units can't share an axis, so the history starts over
+    /// if the units were changed.
+    pub fn push(&mut self, time: u32, intensity: Measurement) {
+        if self.unit != Some(intensity.unit) {
+            *self = Self::default();
+            self.unit = Some(intensity.unit);
+        }
+
+        self.samples[self.next] = Sample {
+            time,
+            intensity: intensity.value.thousandths(),
+        };
+        self.next = (self.next + 1) % HISTORY_LEN;
+        self.len = (self.len + 1).min(HISTORY_LEN);
+    }
+
+    /// Samples from the oldest to the newest.
+    fn iter(&self) -> impl Iterator<Item = &Sample> {
+        let start = (self.next + HISTORY_LEN - self.len) % HISTORY_LEN;
+        (0..self.len).map(move |i| &self.samples[(start + i) % HISTORY_LEN])
+    }
+}
+
+/// Smallest value of 1, 2 or 5 times a power of ten that is at least `value`,
+/// but never below 0.1.
+fn nice_ceiling(value: u32) -> u32 {
+    let mut magnitude: u32 = 100;
+    loop {
+        for step in [1, 2, 5] {
+            let ceiling = magnitude.saturating_mul(step);
+            if ceiling >= value || ceiling == u32::MAX {
+                return ceiling;
+            }
+        }
+        magnitude = magnitude.saturating_mul(10);
+    }
+}
+
+/// Writes a duration like `-45s`, `-12m` or `-3h`.
+fn write_age(s: &mut FuriString, seconds: u32) {
+    match seconds {
+        0..60 => write!(s, "-{seconds}s"),
+        60..3600 => write!(s, "-{}m", seconds / 60),
+        _ => write!(s, "-{}h", seconds / 3600),
+    }
+    .expect("infallible");
+}
+
+pub struct HistoryView;
+
+impl ViewDrawCallback for HistoryView {
+    type Model = History;
+
+    fn callback(canvas: &mut Canvas, history: &Self::Model) {
+        canvas.draw_frame(PLOT_X, PLOT_Y, PLOT_WIDTH, PLOT_HEIGHT);
+
+        if history.len == 0 {
+            canvas.draw_str(PLOT_X + 14, PLOT_Y + 30, c"no rain data");
+            return;
+        }
+
+        let max = history.iter().map(|sample| sample.intensity).max();
+        let scale = nice_ceiling(max.unwrap_or_default());
+        let decimals = match scale {
+            scale if scale % 1000 == 0 => 0,
+            scale if scale % 100 == 0 => 1,
+            _ => 2,
+        };
+
+        // y axis labels
+        let mut label = FuriString::new();
+        write!(label, "{}", Fixed::from_thousandths(scale, decimals)).expect("infallible");
+        canvas.draw_str(0, PLOT_Y + 8, label.as_c_str());
+        canvas.draw_str(0, PLOT_Y + PLOT_HEIGHT as i32 - 1, c"0");
+        if let Some(unit) = history.unit {
+            let mut unit_label = FuriString::new();
+            unit_label.push_str(unit.as_str());
+            let y = PLOT_Y + PLOT_HEIGHT as i32 / 2 + 12;
+            canvas.draw_str(0, y, unit_label.as_c_str());
+        }
+
+        // newest bar at the right edge, scrolling to the left
+        let inner_height = PLOT_HEIGHT - 2;
+        let bottom = PLOT_Y + PLOT_HEIGHT as i32 - 1;
+        let first_x = PLOT_X + 1 + ((HISTORY_LEN - history.len) * BAR_WIDTH) as i32;
+        for (i, sample) in history.iter().enumerate() {
+            let height = sample.intensity as u64 * inner_height as u64 / scale as u64;
+            let height = height as usize;
+            if height == 0 {
+                continue;
+            }
+            let x = first_x + (i * BAR_WIDTH) as i32;
+            canvas.draw_box(x, bottom - height as i32, BAR_WIDTH, height);
+        }
+
+        // x axis labels
+        let now_width = canvas.string_width(c"now") as i32;
+        let now_x = PLOT_X + PLOT_WIDTH as i32 - now_width;
+        canvas.draw_str(now_x, 63, c"now");
+        let oldest = history.iter().next().map(|sample| sample.time);
+        let newest = history.iter().last().map(|sample| sample.time);
+        if let (Some(oldest), Some(newest)) = (oldest, newest) {
+            let mut age = FuriString::new();
+            write_age(&mut age, newest.saturating_sub(oldest));
+            let age_width = canvas.string_width(age.as_c_str()) as i32;
+            let x = first_x.clamp(PLOT_X, PLOT_X + PLOT_WIDTH as i32 - age_width);
+            // with few samples the oldest bar is close to "now", which wins
+            if x + age_width + AXIS_LABEL_GAP <= now_x {
+                canvas.draw_str(x, 63, age.as_c_str());
+            }
+        }
+
+        // ticks for the top and the middle of the scale
+        canvas.draw_line(PLOT_X - 2, PLOT_Y + 1, PLOT_X, PLOT_Y + 1);
+        let middle = PLOT_Y + PLOT_HEIGHT as i32 / 2;
+        canvas.draw_line(PLOT_X - 2, middle, PLOT_X, middle);
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::vec::Vec;
+use flipperzero::furi::{
+    stream_buffer::Receiver,
+    time::{Duration, Instant},
+};
+
+/// Longest line kept, longer ones are dropped as [`LineError::TooLong`].
+pub const MAX_LINE_LEN: usize = 4096;
+
+/// Bytes taken out of the stream buffer at once.
+const CHUNK_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineError {
+    InvalidUtf8,
+    TooLong,
+}
+
+/// Splits the bytes of a stream buffer into lines.
+///
+/// Bytes are received in chunks of whatever is available, so a burst of data
+/// costs a single wake-up instead of one per byte.
+pub struct LineReader {
+    rx: Receiver,
+    chunk: [u8; CHUNK_LEN],
+    // part of `chunk` that isn't split into lines yet
+    start: usize,
+    end: usize,
+    line: Vec<u8>,
+    // the current line got longer than `MAX_LINE_LEN`
+    overflow: bool,
+    // `line` was returned and is cleared before the next one
+    complete: bool,
+}
+
+impl LineReader {
+    pub fn new(rx: Receiver) -> Self {
+        Self {
+            rx,
+            chunk: [0; CHUNK_LEN],
+            start: 0,
+            end: 0,
+            line: Vec::new(),
+            overflow: false,
+            complete: false,
+        }
+    }
+
+    /// Blocks until a complete line arrived and returns it without its line
+    /// ending.
+    ///
+    /// `raw` is called with every chunk as received, e.g. to capture it.
+    pub fn next_line(&mut self, raw: impl FnMut(&[u8])) -> Result<&str, LineError> {
+        self.read(None, raw)
+            .expect("waiting forever doesn't time out")
+    }
+
+    /// Like [`LineReader::next_line`], but `None` if no line was complete
+    /// `timeout` after `start`.
+    ///
+    /// A partial line is kept for the next call.
+    pub fn next_line_until(
+        &mut self,
+        start: Instant,
+        timeout: Duration,
+    ) -> Option<Result<&str, LineError>> {
+        self.read(Some((start, timeout)), |_| ())
+    }
+
+    /// Drops everything received so far, including a partial line.
+    pub fn clear(&mut self) {
+        while self.rx.recv_with_timeout(&mut self.chunk, Duration::ZERO) > 0 {}
+        self.start = 0;
+        self.end = 0;
+        self.line.clear();
+        self.overflow = false;
+        self.complete = false;
+    }
+
+    fn read(
+        &mut self,
+        deadline: Option<(Instant, Duration)>,
+        mut raw: impl FnMut(&[u8]),
+    ) -> Option<Result<&str, LineError>> {
+        if self.complete {
+            self.line.clear();
+            self.overflow = false;
+            self.complete = false;
+        }
+
+        loop {
+            let pending = &self.chunk[self.start..self.end];
+            let newline = pending.iter().position(|&byte| byte == b'\n');
+            let part = &pending[..newline.unwrap_or(pending.len())];
+            match self.line.len() + part.len() <= MAX_LINE_LEN {
+                true => self.line.extend_from_slice(part),
+                false => self.overflow = true,
+            }
+
+            if let Some(newline) = newline {
+                self.start += newline + 1;
+                self.complete = true;
+                return Some(self.finish());
+            }
+            self.start = self.end;
+
+            let wait = match deadline {
+                None => Duration::MAX,
+                Some((start, timeout)) => {
+                    let timeout = timeout.as_millis();
+                    let elapsed = start.elapsed().as_millis();
+                    if elapsed >= timeout {
+                        return None;
+                    }
+                    Duration::from_millis((timeout - elapsed) as u32)
+                }
+            };
+            let received = self.rx.recv_with_timeout(&mut self.chunk, wait);
+            if received > 0 {
+                raw(&self.chunk[..received]);
+            }
+            self.start = 0;
+            self.end = received;
+        }
+    }
+
+    fn finish(&self) -> Result<&str, LineError> {
+        if self.overflow {
+            return Err(LineError::TooLong);
+        }
+        let line = core::str::from_utf8(&self.line).map_err(|_| LineError::InvalidUtf8)?;
+        Ok(line.strip_suffix('\r').unwrap_or(line))
+    }
+}
@@ -0,0 +1,80 @@
+use core::{ffi::CStr, fmt::Write};
+
+use alloc::{ffi::CString, format, string::String};
+use rg15_protocol::{Measurement, Reading, UnitSystem};
+
+use crate::safe::*;
+
+pub const DATA_DIR: &CStr = c"/ext/apps_data/rg15";
+
+const CSV_HEADER: &str = "timestamp,acc,event_acc,total_acc,r_int,units\n";
+
+/// Appends readings to a CSV file per day in [`DATA_DIR`].
+pub struct Logger {
+    file: File,
+    opened: DateTime,
+}
+
+impl Logger {
+    /// Opens today's log file, `None` if there is no SD card.
+    pub fn open() -> Option<Self> {
+        Self::open_at(DateTime::now())
+    }
+
+    fn open_at(now: DateTime) -> Option<Self> {
+        let storage = Storage::open();
+        if !storage.mkdir(DATA_DIR) {
+            return None;
+        }
+
+        let path = format!(
+            "{}/{:04}-{:02}-{:02}.csv",
+            DATA_DIR.to_str().expect("valid utf-8"),
+            now.year,
+            now.month,
+            now.day
+        );
+        let path = CString::new(path).expect("no nul bytes in path");
+        let mut file = File::open(&path, FileAccess::Write, FileOpen::Append)?;
+        if file.size() == 0 {
+            file.write(CSV_HEADER.as_bytes());
+        }
+
+        Some(Self { file, opened: now })
+    }
+
+    pub fn log(&mut self, reading: &Reading) {
+        let now = DateTime::now();
+        if !now.same_day(&self.opened) {
+            if let Some(logger) = Self::open_at(now) {
+                *self = logger;
+            }
+        }
+
+        let mut row = String::new();
+        write!(row, "{now}").expect("infallible");
+        for measurement in [
+            reading.acc,
+            reading.event_acc,
+            reading.total_acc,
+            reading.r_int,
+        ] {
+            row.push(',');
+            if let Some(Measurement { value, .. }) = measurement {
+                write!(row, "{value}").expect("infallible");
+            }
+        }
+        row.push(',');
+        match reading.unit_system() {
+            Some(UnitSystem::Metric) => row.push_str("metric"),
+            Some(UnitSystem::Imperial) => row.push_str("imperial"),
+            None => (),
+        }
+        row.push('\n');
+
+        self.file.write(row.as_bytes());
+        // the app may be closed any time by pulling the sensor cable or the
+        // battery running out, so don't keep rows in a buffer
+        self.file.sync();
+    }
+}
@@ -9,101 +9,386 @@ extern crate flipperzero_rt;
 extern crate alloc;
 extern crate flipperzero_alloc;
 
-use core::{cell::Cell, cmp, ffi::CStr};
+use core::{ffi::CStr, fmt::Write};
 
-use alloc::{borrow::ToOwned, string::String, sync::Arc};
-use cmd::BaudRate;
-use flipperzero::{
-    furi::{self, string::FuriString},
+use alloc::{
+    ffi::CString,
+    format,
+    string::String,
+    sync::{Arc, Weak},
+    vec,
+    vec::Vec,
+};
+use capture::{Capture, Replay, ReplayError, CAPTURE_DIR, CAPTURE_EXTENSION};
+use command_session::{CommandResult, CommandSession};
+use device_info::DeviceInfoView;
+use diagnostics::{Diagnostics, DiagnosticsView, LineStats};
+use flipperzero::furi::{
+    self,
+    string::FuriString,
+    sync::Mutex,
+    time::{Duration, Instant},
 };
 use flipperzero_rt::{entry, manifest};
-use serial_marker::Initialized;
-use ufmt::derive::uDebug;
+use history::{History, HistoryView};
+use line_reader::{LineError, LineReader};
+use logger::{Logger, DATA_DIR};
+use poll::Poller;
+use profile::{Profile, ProfileError, ProfileRun, ProfileView, PROFILE_DIR, PROFILE_EXTENSION};
+use responses::Responses;
+use rg15_protocol::{
+    BaudRate, Command, Reading, Response, Scenario, ScriptError, ScriptStep, SensorConfig,
+};
+use script::{
+    Script, ScriptLoadError, ScriptRun, ScriptState, ScriptView, Transcript, SCRIPT_DIR,
+    SCRIPT_EXTENSION,
+};
+use serial_marker::{Initialized, Uninitialized};
+use settings::{DisplayUnits, Port, Setting, Settings};
+use simulator::Simulator;
+use toast::Toast;
+use transport::{Link, Transport};
+use worker::{Cancel, Worker};
 
 use safe::*;
 
-mod cmd;
+mod auto_baud;
+mod baud_change;
+mod capture;
+mod command_session;
+mod device_info;
+mod diagnostics;
+mod history;
+mod line_reader;
+mod logger;
+mod menu;
+mod poll;
+mod profile;
+mod responses;
+mod run_view;
 mod safe;
+mod script;
+mod settings;
+mod simulator;
+mod toast;
+mod transport;
+mod worker;
 
 manifest!(name = "RG-15");
 entry!(main);
 
-const BAUD_RATE_VIEW_ID: u32 = 0;
 const MAIN_VIEW_ID: u32 = 1;
 const RAW_VIEW_ID: u32 = 2;
 const CMD_VIEW_ID: u32 = 3;
+const HISTORY_VIEW_ID: u32 = 4;
+const SETTINGS_VIEW_ID: u32 = 5;
+const MENU_VIEW_ID: u32 = 6;
+const DIAGNOSTICS_VIEW_ID: u32 = 7;
+const CUSTOM_CMD_VIEW_ID: u32 = 8;
+const CONFIRM_VIEW_ID: u32 = 9;
+const DEVICE_INFO_VIEW_ID: u32 = 10;
+const PROFILE_VIEW_ID: u32 = 11;
+const SCRIPT_VIEW_ID: u32 = 12;
+
+/// Submenu index of the custom command entry, no [`Command::code`] uses it.
+const CUSTOM_CMD_CODE: u32 = 100;
+/// Longest custom command that can be entered.
+const CUSTOM_CMD_MAX_LEN: usize = 32;
+/// Time in ms an `expect` step of a script waits for its line.
+const EXPECT_TIMEOUT: u32 = 5000;
+
+/// Arrow on the poll button, 7 by 4 pixels.
+const DOWN_ARROW: Xbm = Xbm::new(7, 4, &[0x7f, 0x3e, 0x1c, 0x08]);
 
 const SCREEN_HEIGHT: u32 = 64;
 const SCREEN_WIDTH: u32 = 128;
 
+const SOURCE_SUBMENU_HEADER: &'static CStr = c"Data Source";
+const SCENARIO_SUBMENU_HEADER: &'static CStr = c"Rain Scenario";
+const PORT_SUBMENU_HEADER: &'static CStr = c"Connect RG-15 to";
 const BAUD_RATE_SUBMENU_HEADER: &'static CStr = c"Select Baud Rate";
+const BAUD_RATE_FALLBACK_HEADER: &'static CStr = c"No Response, Select Rate";
 const CMD_SUBMENU_HEADER: &'static CStr = c"Command to RG-15";
+const CUSTOM_CMD_HEADER: &'static CStr = c"Command to send";
+const CONFIRM_TEXT: &'static CStr = c"This can't be undone\non the sensor. Send it?";
+const MENU_SUBMENU_HEADER: &'static CStr = c"RG-15";
 
-fn select_baud_rate() -> Option<BaudRate> {
-    let gui = Gui::open();
-    let view_dispatcher = ViewDispatcher::new(gui, ViewDispatcherType::Fullscreen);
-    
-    struct Context {
-        view_dispatcher: ViewDispatcher,
-        baud_rate: Cell<Option<BaudRate>>,
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Serial,
+    SerialCapture,
+    Replay,
+    Simulator,
+}
+
+impl Source {
+    fn list() -> [Self; 4] {
+        [
+            Source::Serial,
+            Source::SerialCapture,
+            Source::Replay,
+            Source::Simulator,
+        ]
     }
 
-    let mut context = Arc::new(Context {
-        view_dispatcher,
-        baud_rate: Cell::new(None)
-    });
+    fn name(self) -> &'static CStr {
+        match self {
+            Source::Serial => c"Serial",
+            Source::SerialCapture => c"Serial + Capture",
+            Source::Replay => c"Replay Capture",
+            Source::Simulator => c"Simulator",
+        }
+    }
+
+    fn try_from_index(index: u32) -> Option<Self> {
+        Self::list().get(index as usize).copied()
+    }
+}
+
+fn select_source() -> Option<Source> {
+    let items = Source::list().map(|source| (source.name(), source as u32));
+    let index = menu::choose(SOURCE_SUBMENU_HEADER, &items, None)?;
+    Source::try_from_index(index)
+}
+
+fn select_scenario() -> Option<Scenario> {
+    let scenarios = Scenario::list();
+    let items: Vec<_> = (0..)
+        .zip(scenarios)
+        .map(|(index, scenario)| (scenario.name(), index))
+        .collect();
+    let index = menu::choose(SCENARIO_SUBMENU_HEADER, &items, None)?;
+    scenarios.get(index as usize).copied()
+}
+
+fn select_port() -> Option<Port> {
+    let items = Port::list().map(|port| (port.name_with_pins(), port as u32));
+    let index = menu::choose(PORT_SUBMENU_HEADER, &items, None)?;
+    Port::list().get(index as usize).copied()
+}
+
+/// Acquires the serial port, if something else holds it the user may retry,
+/// switch to the other port or exit.
+fn acquire_serial(mut port: Port) -> Option<(Port, SerialHandle<Uninitialized>)> {
+    let dialogs = Dialogs::open();
+    loop {
+        if let Some(serial_handle) = SerialHandle::acquire(port.serial_id()) {
+            return Some((port, serial_handle));
+        }
 
-    struct SelectBaudRateItem;
+        let name = port.name().to_str().expect("valid utf-8");
+        let text = format!("{name} is in use by\nthe CLI or an\nexpansion module.");
+        let text = CString::new(text).expect("no nul bytes in text");
+        let mut message = DialogMessage::new();
+        message.set_header(c"Serial Port Busy", 64, 2, Align::Center, Align::Top);
+        message.set_text(&text, 64, 32, Align::Center, Align::Center);
+        message.set_buttons(Some(c"Exit"), Some(c"Retry"), Some(port.other().name()));
 
-    impl SubmenuItem for SelectBaudRateItem {
-        type Context = Context;
-    
-        fn select(context: &Self::Context, baud_rate: u32) {
-            let Ok(baud_rate) = BaudRate::try_from(baud_rate as u16) else { return };
-            context.baud_rate.set(Some(baud_rate));
-            context.view_dispatcher.stop();
+        match dialogs.show_message(&message) {
+            DialogMessageButton::Center => (),
+            DialogMessageButton::Right => port = port.other(),
+            DialogMessageButton::Left | DialogMessageButton::Back => return None,
         }
     }
+}
 
-    let mut submenu = Submenu::new();
-    let submenu_view = submenu.as_mut_view();
-    submenu_view.set_previous_callback::<ViewNone>();
-    submenu.set_header(BAUD_RATE_SUBMENU_HEADER);
-    for baud_rate in BaudRate::list() {
-        submenu.add_item::<SelectBaudRateItem, _>(baud_rate.rate_as_char(), baud_rate.rate() as u32, Some(context.clone()));
+/// Entries of the menu opened with OK in the main view.
+#[derive(Debug, Clone, Copy)]
+enum MenuEntry {
+    Settings,
+    ApplyProfile,
+    RunScript,
+    DeviceInfo,
+    Diagnostics,
+}
+
+impl MenuEntry {
+    fn list() -> [Self; 5] {
+        [
+            MenuEntry::Settings,
+            MenuEntry::ApplyProfile,
+            MenuEntry::RunScript,
+            MenuEntry::DeviceInfo,
+            MenuEntry::Diagnostics,
+        ]
     }
-    submenu.set_selected_item(BaudRate::default().rate() as u32);
 
-    let context = Arc::get_mut(&mut context).expect("submenu::add_item doesn't really store Arcs yet");
-    context.view_dispatcher.add_submenu(submenu, BAUD_RATE_VIEW_ID);
-    context.view_dispatcher.switch_to_view(BAUD_RATE_VIEW_ID);
-    context.view_dispatcher.run();
+    fn name(self) -> &'static CStr {
+        match self {
+            MenuEntry::Settings => c"Settings",
+            MenuEntry::ApplyProfile => c"Apply Profile",
+            MenuEntry::RunScript => c"Run Script",
+            MenuEntry::DeviceInfo => c"Sensor Config",
+            MenuEntry::Diagnostics => c"Diagnostics",
+        }
+    }
 
-    context.baud_rate.get()
+    fn view_id(self) -> u32 {
+        match self {
+            MenuEntry::Settings => SETTINGS_VIEW_ID,
+            MenuEntry::ApplyProfile => PROFILE_VIEW_ID,
+            MenuEntry::RunScript => SCRIPT_VIEW_ID,
+            MenuEntry::DeviceInfo => DEVICE_INFO_VIEW_ID,
+            MenuEntry::Diagnostics => DIAGNOSTICS_VIEW_ID,
+        }
+    }
+
+    fn try_from_index(index: u32) -> Option<Self> {
+        Self::list().get(index as usize).copied()
+    }
+}
+
+const AUTO_BAUD_RATE_INDEX: u32 = 0;
+
+#[derive(Debug, Clone, Copy)]
+enum BaudRateChoice {
+    Auto,
+    Manual(BaudRate),
+}
+
+fn select_baud_rate(
+    serial_handle: &SerialHandle<Initialized>,
+    lines: &mut LineReader,
+    last: Option<BaudRate>,
+) -> Option<BaudRate> {
+    let mut header = BAUD_RATE_SUBMENU_HEADER;
+
+    // the sensor keeps its rate, so the last one usually still works
+    if let Some(baud_rate) = last {
+        if auto_baud::probe(serial_handle, lines, baud_rate) {
+            return Some(baud_rate);
+        }
+        header = BAUD_RATE_FALLBACK_HEADER;
+    }
+
+    loop {
+        match baud_rate_menu(header, last)? {
+            BaudRateChoice::Manual(baud_rate) => return Some(baud_rate),
+            BaudRateChoice::Auto => match auto_baud::detect(serial_handle, lines) {
+                Some(baud_rate) => return Some(baud_rate),
+                None => header = BAUD_RATE_FALLBACK_HEADER,
+            },
+        }
+    }
+}
+
+fn baud_rate_menu(header: &CStr, last: Option<BaudRate>) -> Option<BaudRateChoice> {
+    let mut items = vec![(c"Auto", AUTO_BAUD_RATE_INDEX)];
+    for baud_rate in BaudRate::list() {
+        items.push((baud_rate.rate_as_char(), baud_rate.rate() as u32));
+    }
+
+    let selected = last.unwrap_or_default().rate() as u32;
+    let index = menu::choose(header, &items, Some(selected))?;
+    match index {
+        AUTO_BAUD_RATE_INDEX => Some(BaudRateChoice::Auto),
+        baud_rate => BaudRate::try_from(baud_rate as u16)
+            .ok()
+            .map(BaudRateChoice::Manual),
+    }
 }
 
 fn main(_args: Option<&CStr>) -> i32 {
-    let Some(baud_rate) = select_baud_rate() else { return 0 };
+    let mut settings = Settings::load();
+    let Some(source) = select_source() else {
+        return 0;
+    };
+
+    let (link, mut lines) = match source {
+        Source::Replay => {
+            let dialogs = Dialogs::open();
+            // back to the browser until a capture starts
+            let (replay, rx) = loop {
+                let Some(path) = dialogs.browse_file(CAPTURE_DIR, CAPTURE_EXTENSION) else {
+                    return 0;
+                };
+                let text = match Replay::start(path.as_c_str()) {
+                    Ok(started) => break started,
+                    Err(ReplayError::Unreadable) => "Can't read the file",
+                    Err(ReplayError::InvalidHeader) => "No capture header",
+                };
+                show_error(&dialogs, c"Replay Failed", String::from(text));
+            };
+            (Link::Replay(replay), LineReader::new(rx))
+        }
+        Source::Simulator => {
+            let Some(scenario) = select_scenario() else {
+                return 0;
+            };
+            let (simulator, rx) = Simulator::start(scenario);
+            (Link::Simulator(simulator), LineReader::new(rx))
+        }
+        Source::Serial | Source::SerialCapture => {
+            let Some(port) = settings.port.or_else(select_port) else {
+                return 0;
+            };
+            let Some((port, serial_handle)) = acquire_serial(port) else {
+                return 1;
+            };
+            if settings.port != Some(port) {
+                settings.port = Some(port);
+                settings.save();
+            }
+            let mut serial_handle: SerialHandle<_> =
+                serial_handle.init(BaudRate::default().rate() as u32);
+            let mut lines = LineReader::new(serial_handle.async_rx_start(true));
+
+            let Some(baud_rate) = select_baud_rate(&serial_handle, &mut lines, settings.baud_rate)
+            else {
+                return 0;
+            };
+            serial_handle.set_br(baud_rate.rate() as u32);
+            // a manually selected rate is only remembered once the sensor answered
+            let mut answered = || auto_baud::probe(&serial_handle, &mut lines, baud_rate);
+            if settings.baud_rate != Some(baud_rate) && answered() {
+                settings.baud_rate = Some(baud_rate);
+                settings.save();
+            }
+            (Link::Serial(serial_handle), lines)
+        }
+    };
+    let transport = Transport::new(link);
+    let mut capture = match source {
+        Source::SerialCapture => {
+            let capture = Capture::create(transport.baud_rate());
+            if capture.is_none() {
+                let text = String::from("Can't write to the SD card,\nrunning without capture");
+                show_error(&Dialogs::open(), c"Capture Failed", text);
+            }
+            capture
+        }
+        Source::Serial | Source::Replay | Source::Simulator => None,
+    };
 
     let gui = Gui::open();
     let mut view_dispatcher = ViewDispatcher::new(gui, ViewDispatcherType::Fullscreen);
     let view_switcher = view_dispatcher.view_switcher();
 
-    let Some(serial_handle) = SerialHandle::acquire(SerialId::Usart) else {
-        return 1;
-    };
-    let mut serial_handle: SerialHandle<_> = serial_handle.init(baud_rate.rate() as u32);
-    let rx = serial_handle.async_rx_start(false);
-
+    let responses = Arc::new(Responses::new());
+    let line_stats = Arc::new(LineStats::default());
+    let rx_errors = transport.rx_errors();
+    let polls = transport.polls();
+    // the sensor answered at the rate we talk at, or it wouldn't have got here
+    let sensor_config = Arc::new(Mutex::new(SensorConfig {
+        baud_rate: BaudRate::try_from(transport.baud_rate() as u16).ok(),
+        ..SensorConfig::default()
+    }));
+    let shared_settings = Arc::new(Mutex::new(settings));
     let context = Arc::new(CallbackContext {
-        serial_handle,
+        transport,
         view_switcher,
+        responses: responses.clone(),
+        poller: Poller::new(),
+        settings: shared_settings.clone(),
+        sensor_config: sensor_config.clone(),
+        worker: Worker::new(),
     });
+    context.poller.set_interval(settings.poll_interval);
 
-    let mut main_view = View::new();
-    main_view.set_context(context.clone());
-    main_view.create_model::<Data>();
+    let mut main_view = View::<CallbackContext, Data>::with_context(context.clone());
+    main_view.model().model.units = settings.units;
     main_view.set_previous_callback::<ViewNone>();
+    main_view.set_enter_callback::<MainView>();
     main_view.set_draw_callback::<MainView>();
     main_view.set_input_callback::<MainView>();
     let main_view = view_dispatcher.add_view(main_view, MAIN_VIEW_ID);
@@ -118,114 +403,378 @@ fn main(_args: Option<&CStr>) -> i32 {
     let cmd_view = cmd_submenu.as_mut_view();
     cmd_view.set_previous_callback::<OtherView>();
     cmd_submenu.set_header(CMD_SUBMENU_HEADER);
-    for cmd in cmd::Command::list() {
-        cmd_submenu.add_item::<CmdSubmenuItem, _>(cmd.name(), cmd.code(), Some(context.clone()));
+    let mut confirm_dialog = DialogEx::new();
+    let confirm_view = confirm_dialog.as_mut_view();
+    confirm_view.set_previous_callback::<CmdView>();
+    confirm_dialog.set_text(CONFIRM_TEXT, 64, 32, Align::Center, Align::Center);
+    confirm_dialog.set_buttons(Some(c"Cancel"), None, Some(c"Send"));
+    let confirm_dialog = view_dispatcher.add_dialog_ex_mutex(confirm_dialog, CONFIRM_VIEW_ID);
+
+    let cmd_context = Arc::new(CmdItemContext {
+        context: context.clone(),
+        main_view: main_view.clone(),
+        confirm_dialog: confirm_dialog.clone(),
+        pending: Mutex::new(None),
+    });
+    if let Some(confirm_dialog) = confirm_dialog.upgrade() {
+        let mut confirm_dialog = confirm_dialog.lock();
+        confirm_dialog.set_result_callback::<ConfirmDialog>(cmd_context.clone());
     }
+    for cmd in Command::list() {
+        cmd_submenu.add_item::<CmdSubmenuItem, _>(
+            cmd.name(),
+            cmd.code(),
+            Some(cmd_context.clone()),
+        );
+    }
+    cmd_submenu.add_item::<CmdSubmenuItem, _>(
+        c"Custom command...",
+        CUSTOM_CMD_CODE,
+        Some(cmd_context.clone()),
+    );
     view_dispatcher.add_submenu(cmd_submenu, CMD_VIEW_ID);
 
+    let mut custom_cmd_input = TextInput::new(CUSTOM_CMD_MAX_LEN);
+    let custom_cmd_view = custom_cmd_input.as_mut_view();
+    custom_cmd_view.set_previous_callback::<CmdView>();
+    custom_cmd_input.set_header(CUSTOM_CMD_HEADER);
+    custom_cmd_input.set_result_callback::<CustomCmdInput>(cmd_context.clone());
+    view_dispatcher.add_text_input(custom_cmd_input, CUSTOM_CMD_VIEW_ID);
+
+    let mut settings_list = VariableItemList::new();
+    let settings_view = settings_list.as_mut_view();
+    settings_view.set_previous_callback::<MenuView>();
+    for setting in Setting::list() {
+        let item_context = Arc::new(SettingsItemContext {
+            context: context.clone(),
+            main_view: main_view.clone(),
+            setting,
+        });
+        let mut item = settings_list.add_item::<SettingsItem>(
+            setting.label(),
+            setting.values_count(),
+            item_context,
+        );
+        let index = setting.value_index(&settings);
+        item.set_current_value_index(index);
+        item.set_current_value_text(setting.value_name(index));
+    }
+    view_dispatcher.add_variable_item_list(settings_list, SETTINGS_VIEW_ID);
+
+    let mut history_view = View::<(), History>::new();
+    history_view.set_previous_callback::<OtherView>();
+    history_view.set_draw_callback::<HistoryView>();
+    let history_view = view_dispatcher.add_view(history_view, HISTORY_VIEW_ID);
+
+    let mut menu_submenu = Submenu::new();
+    let menu_view = menu_submenu.as_mut_view();
+    menu_view.set_previous_callback::<OtherView>();
+    menu_submenu.set_header(MENU_SUBMENU_HEADER);
+    let profile_context = Arc::new(ProfileContext {
+        context: context.clone(),
+        profile_view: Mutex::new(Weak::new()),
+    });
+    let mut profile_view =
+        View::<ProfileContext, ProfileRun>::with_context(profile_context.clone());
+    profile_view.set_previous_callback::<CancelRun>();
+    profile_view.set_draw_callback::<ProfileView>();
+    profile_view.set_input_callback::<ProfileInput>();
+    *profile_context.profile_view.lock() = view_dispatcher.add_view(profile_view, PROFILE_VIEW_ID);
+    let mut script_view = View::<CallbackContext, ScriptRun>::with_context(context.clone());
+    script_view.set_previous_callback::<CancelRun>();
+    script_view.set_draw_callback::<ScriptView>();
+    let script_view = view_dispatcher.add_view(script_view, SCRIPT_VIEW_ID);
+    let script_context = Arc::new(ScriptContext {
+        context: context.clone(),
+        script_view,
+    });
+
+    for entry in MenuEntry::list() {
+        match entry {
+            MenuEntry::ApplyProfile => menu_submenu.add_item::<ProfileSubmenuItem, _>(
+                entry.name(),
+                entry as u32,
+                Some(profile_context.clone()),
+            ),
+            MenuEntry::RunScript => menu_submenu.add_item::<ScriptSubmenuItem, _>(
+                entry.name(),
+                entry as u32,
+                Some(script_context.clone()),
+            ),
+            _ => menu_submenu.add_item::<MenuSubmenuItem, _>(
+                entry.name(),
+                entry as u32,
+                Some(context.clone()),
+            ),
+        }
+    }
+    view_dispatcher.add_submenu(menu_submenu, MENU_VIEW_ID);
+
+    let mut diagnostics_view = View::<(), Diagnostics>::new();
+    diagnostics_view.set_previous_callback::<MenuView>();
+    diagnostics_view.set_draw_callback::<DiagnosticsView>();
+    let diagnostics_view = view_dispatcher.add_view(diagnostics_view, DIAGNOSTICS_VIEW_ID);
+
+    let mut device_info_view = View::<(), SensorConfig>::new();
+    device_info_view.set_previous_callback::<MenuView>();
+    device_info_view.set_draw_callback::<DeviceInfoView>();
+    let device_info_view = view_dispatcher.add_view(device_info_view, DEVICE_INFO_VIEW_ID);
+
+    let ticker_context = Arc::new(TickerContext {
+        context: context.clone(),
+        main_view: main_view.clone(),
+        diagnostics_view,
+        device_info_view,
+        rx_errors,
+        line_stats: line_stats.clone(),
+    });
+    let ticker = Timer::new::<Ticker>(TimerType::Periodic, ticker_context);
+    ticker.start(Duration::from_secs(1));
+
     let rx_thread = furi::thread::Builder::new()
         .stack_size(8192)
         .spawn(move || {
-            let mut buf = [0u8; 4096 + 1];
-            let mut i = 0;
+            let mut logger = None;
+            // no retries for every line, only once logging is turned on again
+            let mut logger_failed = false;
+            let start = Instant::now();
+
+            loop {
+                // blocks until a line arrived, the transport sends one more
+                // when it's dropped to wake us up
+                let line = lines.next_line(|bytes| {
+                    if let Some(capture) = capture.as_mut() {
+                        capture.record(bytes);
+                    }
+                });
 
-            while rx.is_sender_alive() {
                 // keep it in here to properly destroy the view if not needed anymore
                 let Some(view) = main_view.upgrade() else {
                     return 0;
                 };
 
-                let mut byte = [0u8];
-                let received =
-                    rx.recv_with_timeout(&mut byte, furi::time::Duration::from_millis(200));
-                if received == 0 {
-                    continue;
+                let line = match line {
+                    Ok(line) => line,
+                    Err(LineError::TooLong) => {
+                        line_stats.count_too_long();
+                        continue;
+                    }
+                    Err(LineError::InvalidUtf8) => {
+                        line_stats.count_invalid_utf8();
+                        continue;
+                    }
+                };
+                line_stats.count_received();
+                let line = line.trim();
+                responses.push(line);
+                if let Ok(response) = Response::parse(line) {
+                    let mut sensor_config = sensor_config.lock();
+                    match polls.as_deref() {
+                        Some(polls) => sensor_config.observe_unpolled(&response, polls),
+                        None => sensor_config.observe(&response),
+                    }
                 }
 
-                let byte = byte[0];
-                buf[i] = byte;
-                i = cmp::min(i + 1, 4096);
+                let settings = *shared_settings.lock();
+                let mut logger_toast = None;
+                match settings.logging {
+                    true if logger.is_none() && !logger_failed => {
+                        logger = Logger::open();
+                        if logger.is_none() {
+                            logger_failed = true;
+                            logger_toast =
+                                Some(Toast::new(c"Logging Failed", "Can't write to SD card"));
+                        }
+                    }
+                    true => (),
+                    false => {
+                        logger = None;
+                        logger_failed = false;
+                    }
+                }
 
-                if byte != b'\n' {
-                    continue;
+                // lines that aren't readings (e.g. command echoes) only go to the raw view
+                let reading = Reading::parse(line).ok();
+                if let (Some(logger), Some(reading)) = (logger.as_mut(), reading.as_ref()) {
+                    logger.log(reading);
                 }
-                let line = String::from_utf8(buf[0..i].to_owned());
-
-                // reset buffer
-                buf.fill(0);
-                i = 0;
-
-                let Ok(line) = line else { continue };
-                if let Some(mut model) = view.get_model::<Data>() {
-                    let model = &mut model.model;
-
-                    let line = line.trim();
-                    model.raw.push_str(line);
-                    model.raw.push('\n');
-                    let split = line.split(',');
-                    for split in split {
-                        let split = split.trim();
-                        let mut split = split.splitn(2, ' ');
-                        let Some(key) = split.next() else { continue };
-                        let Some(value) = split.next() else { continue };
-                        match key {
-                            "Acc" => model.acc = FuriString::from(value),
-                            "EventAcc" => model.event_acc = FuriString::from(value),
-                            "TotalAcc" => model.total_acc = FuriString::from(value),
-                            "RInt" => model.r_int = FuriString::from(value),
-                            _ => continue,
-                        }
+                if let Some(r_int) = reading.and_then(|reading| reading.r_int) {
+                    let time = (start.elapsed().as_millis() / 1000) as u32;
+                    if let Some(history_view) = history_view.upgrade() {
+                        let history = history_view.model();
+                        history.model.push(time, settings.units.apply(r_int));
                     }
+                }
 
-                    let Some(raw_widget) = raw_widget.upgrade() else {
-                        continue;
-                    };
-                    let mut raw_widget = raw_widget.lock();
-                    raw_widget.reset();
-                    raw_widget.add_text_scroll_element(
-                        0,
-                        0,
-                        SCREEN_WIDTH as u8,
-                        SCREEN_HEIGHT as u8,
-                        model.raw.as_c_str(),
-                    );
+                let mut model = view.model();
+                let model = &mut model.model;
+
+                if logger_toast.is_some() {
+                    model.toast = logger_toast;
+                }
+                model.raw.push_str(line);
+                model.raw.push('\n');
+                if let Some(reading) = reading {
+                    model.reading.merge(reading);
+                }
+
+                let Some(raw_widget) = raw_widget.upgrade() else {
+                    continue;
                 };
+                let mut raw_widget = raw_widget.lock();
+                raw_widget.reset();
+                raw_widget.add_text_scroll_element(
+                    0,
+                    0,
+                    SCREEN_WIDTH as u8,
+                    SCREEN_HEIGHT as u8,
+                    model.raw.as_c_str(),
+                );
             }
-
-            0
         });
 
     view_dispatcher.run();
+    // before its job could drop the last reference to the context
+    context.worker.stop();
+    drop(ticker);
+    // also drops the submenus with their item contexts
     drop(view_dispatcher);
+    drop(cmd_context);
+    drop(profile_context);
+    drop(script_context);
+    // the last reference, drops the transport, which wakes up the rx thread
+    drop(context);
     rx_thread.join();
 
     0
 }
 
-#[derive(Debug, uDebug)]
+#[derive(Debug)]
 struct Data {
     raw: FuriString,
-    acc: FuriString,
-    event_acc: FuriString,
-    total_acc: FuriString,
-    r_int: FuriString,
+    reading: Reading,
+    /// Seconds until the next automatic poll.
+    poll_in: Option<u32>,
+    units: DisplayUnits,
+    /// Result of the last command.
+    toast: Option<Toast>,
 }
 
 impl Default for Data {
     fn default() -> Self {
         Self {
             raw: FuriString::from(""),
-            acc: FuriString::from("acc"),
-            event_acc: FuriString::from("event_acc"),
-            total_acc: FuriString::from("total_acc"),
-            r_int: FuriString::from("r_int"),
+            reading: Reading::default(),
+            poll_in: None,
+            units: DisplayUnits::default(),
+            toast: None,
         }
     }
 }
 
 struct CallbackContext {
-    serial_handle: SerialHandle<Initialized>,
+    transport: Transport,
     view_switcher: ViewSwitcher,
+    responses: Arc<Responses>,
+    poller: Poller,
+    settings: Arc<Mutex<Settings>>,
+    sensor_config: Arc<Mutex<SensorConfig>>,
+    worker: Worker,
+}
+
+impl CallbackContext {
+    fn poll(&self) {
+        self.transport.tx(c"r\r\n".to_bytes());
+        self.poller.restart();
+    }
+
+    /// Sends `cmd` in a [`CommandSession`] and remembers a baud rate the
+    /// sensor switched to.
+    fn send_command(&self, cmd: Command) -> CommandResult {
+        let session = CommandSession::new(&self.transport, &self.responses);
+        let result = session.send(cmd);
+
+        let Command::BaudRate(baud_rate) = cmd else {
+            return result;
+        };
+        // the config took the new rate from the acknowledgement, the one of
+        // the revert arrives at the wrong rate and never parses
+        let settled = match result {
+            CommandResult::Answered(_) => Some(baud_rate),
+            CommandResult::NoAnswer => BaudRate::try_from(self.transport.baud_rate() as u16).ok(),
+            CommandResult::SensorLost => None,
+        };
+        self.sensor_config.lock().baud_rate = settled;
+
+        // only the real sensor's rate is worth remembering
+        if result.is_answered() && self.transport.is_serial() {
+            let mut settings = self.settings.lock();
+            settings.baud_rate = Some(baud_rate);
+            settings.save();
+        }
+
+        result
+    }
+
+    /// Starts `job` on the worker, returns whether it was started as the
+    /// worker runs one job at a time.
+    ///
+    /// Automatic polls wait until the job is done, they'd interfere with the
+    /// answers to its commands.
+    fn start_job(
+        self: &Arc<Self>,
+        job: impl FnOnce(&CallbackContext, &Cancel) + Send + 'static,
+    ) -> bool {
+        let context = self.clone();
+        self.worker.start(move |cancel| {
+            context.poller.pause();
+            job(&context, cancel);
+            context.poller.resume();
+        })
+    }
+}
+
+struct TickerContext {
+    context: Arc<CallbackContext>,
+    main_view: Weak<View<CallbackContext, Data>>,
+    diagnostics_view: Weak<View<(), Diagnostics>>,
+    device_info_view: Weak<View<(), SensorConfig>>,
+    rx_errors: Option<Arc<RxErrors>>,
+    line_stats: Arc<LineStats>,
+}
+
+/// Runs once a second for the automatic polls and to refresh the counters and
+/// the sensor config.
+struct Ticker;
+
+impl TimerCallback for Ticker {
+    type Context = TickerContext;
+
+    fn callback(context: &Self::Context) {
+        if context.context.poller.tick() {
+            context.context.poll();
+        }
+
+        if let Some(view) = context.main_view.upgrade() {
+            let data = view.model();
+            data.model.poll_in = context.context.poller.remaining();
+            if data.model.toast.as_mut().is_some_and(|toast| !toast.tick()) {
+                data.model.toast = None;
+            }
+        }
+
+        if let Some(view) = context.diagnostics_view.upgrade() {
+            let diagnostics = view.model();
+            diagnostics
+                .model
+                .update(context.rx_errors.as_deref(), &context.line_stats);
+        }
+
+        if let Some(view) = context.device_info_view.upgrade() {
+            let config = view.model();
+            *config.model = *context.context.sensor_config.lock();
+        }
+    }
 }
 
 struct MainView;
@@ -233,48 +782,77 @@ struct MainView;
 impl ViewDrawCallback for MainView {
     type Model = Data;
 
-    fn callback(canvas: &mut Canvas, model: Option<&Self::Model>) {
+    fn callback(canvas: &mut Canvas, data: &Self::Model) {
         canvas.elements_button_right(c"raw");
         canvas.elements_button_left(c"cmd");
 
-        const POLL_WIDTH: u32 = 34;
+        // counts down to the next automatic poll if enabled
+        let mut poll_label = FuriString::new();
+        match data.poll_in {
+            Some(seconds @ 0..60) => write!(poll_label, "{seconds}s").expect("infallible"),
+            Some(seconds) => {
+                write!(poll_label, "{}:{:02}", seconds / 60, seconds % 60).expect("infallible")
+            }
+            None => poll_label.push_str("poll"),
+        }
+        let poll_label = poll_label.as_c_str();
+
+        let poll_width: u32 = 19 + canvas.string_width(poll_label) as u32;
         const POLL_HEIGHT: u32 = 14;
-        let box_x = ((SCREEN_WIDTH - POLL_WIDTH) / 2) as i32;
+        let box_x = ((SCREEN_WIDTH - poll_width) / 2) as i32;
         let box_y = ((SCREEN_HEIGHT - POLL_HEIGHT) + 2) as i32;
 
-        let down_arrow = |canvas: &mut Canvas, x, y| {
-            for yi in 0..4 {
-                for xi in (0 + yi)..(7 - yi) {
-                    canvas.draw_dot(x + xi, y + yi);
-                }
-            }
-        };
-
         canvas.draw_rbox(
             box_x as i32,
             box_y as i32,
-            POLL_WIDTH as usize,
+            poll_width as usize,
             POLL_HEIGHT as usize,
             3,
         );
         canvas.invert_color();
-        down_arrow(canvas, box_x + 5, box_y + 4);
-        canvas.draw_str(box_x + 15, box_y + 9, c"poll");
+        canvas.draw_xbm(box_x + 5, box_y + 4, DOWN_ARROW);
+        canvas.draw_str(box_x + 15, box_y + 9, poll_label);
         canvas.invert_color();
 
-        if let Some(data) = model {
-            [c"last acc:", c"event acc:", c"total acc:", c"rain int:"]
-                .iter()
-                .enumerate()
-                .map(|(i, s)| (s, (i as i32 + 1) * 11))
-                .zip([&data.acc, &data.event_acc, &data.total_acc, &data.r_int].into_iter())
-                .for_each(|((label, y), data)| {
-                    let padding = 10;
-                    canvas.draw_str(padding, y, label);
-                    let data = data.as_c_str();
-                    let data_width = canvas.string_width(data) as u32;
-                    canvas.draw_str((SCREEN_WIDTH - data_width) as i32 - padding, y, data);
-                });
+        let units = data.units;
+        [c"last acc:", c"event acc:", c"total acc:", c"rain int:"]
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s, (i as i32 + 1) * 11))
+            .zip([
+                data.reading.acc,
+                data.reading.event_acc,
+                data.reading.total_acc,
+                data.reading.r_int,
+            ])
+            .for_each(|((label, y), measurement)| {
+                let padding = 10;
+                canvas.draw_str(padding, y, label);
+                let mut data = FuriString::new();
+                match measurement {
+                    Some(measurement) => {
+                        write!(data, "{}", units.apply(measurement)).expect("infallible")
+                    }
+                    None => data.push_str("-"),
+                }
+                let data = data.as_c_str();
+                let data_width = canvas.string_width(data) as u32;
+                canvas.draw_str((SCREEN_WIDTH - data_width) as i32 - padding, y, data);
+            });
+
+        if let Some(toast) = data.toast.as_ref() {
+            toast.draw(canvas);
+        }
+    }
+}
+
+impl ViewEnterCallback for MainView {
+    type Context = CallbackContext;
+
+    fn callback(context: &Self::Context) {
+        // a running job resumes them once it's done
+        if !context.worker.is_busy() {
+            context.poller.resume();
         }
     }
 }
@@ -282,12 +860,25 @@ impl ViewDrawCallback for MainView {
 impl ViewInputCallback for MainView {
     type Context = CallbackContext;
 
-    fn callback(input_key: InputKey, context: Option<&Self::Context>) -> bool {
-        let Some(context) = context else { return false };
-        match input_key {
-            InputKey::Down => context.serial_handle.tx(c"r\r\n".to_bytes()),
-            InputKey::Right => context.view_switcher.switch_to_view(RAW_VIEW_ID),
-            InputKey::Left => context.view_switcher.switch_to_view(CMD_VIEW_ID),
+    fn callback(event: InputEvent, context: &Self::Context) -> bool {
+        match (event.key, event.kind) {
+            // holding Down keeps polling
+            (InputKey::Down, InputType::Short | InputType::Long | InputType::Repeat) => {
+                context.poll()
+            }
+            (InputKey::Right, InputType::Short) => {
+                context.view_switcher.switch_to_view(RAW_VIEW_ID)
+            }
+            (InputKey::Left, InputType::Short) => {
+                // polls would interfere with the answers to the commands,
+                // resumed when this view is shown again
+                context.poller.pause();
+                context.view_switcher.switch_to_view(CMD_VIEW_ID);
+            }
+            (InputKey::Up, InputType::Short) => {
+                context.view_switcher.switch_to_view(HISTORY_VIEW_ID)
+            }
+            (InputKey::Ok, InputType::Short) => context.view_switcher.switch_to_view(MENU_VIEW_ID),
             _ => return false,
         }
 
@@ -297,29 +888,455 @@ impl ViewInputCallback for MainView {
 
 struct OtherView;
 
-impl ViewNavigationCallback for OtherView {
-    type Context = ();
-
-    fn callback(_: Option<&Self::Context>) -> u32 {
+impl<C> ViewNavigationCallback<C> for OtherView {
+    fn callback(_: &C) -> u32 {
         MAIN_VIEW_ID
     }
 }
 
+struct MenuView;
+
+impl<C> ViewNavigationCallback<C> for MenuView {
+    fn callback(_: &C) -> u32 {
+        MENU_VIEW_ID
+    }
+}
+
+struct CmdView;
+
+impl<C> ViewNavigationCallback<C> for CmdView {
+    fn callback(_: &C) -> u32 {
+        CMD_VIEW_ID
+    }
+}
+
+struct MenuSubmenuItem;
+
+impl SubmenuItem for MenuSubmenuItem {
+    type Context = CallbackContext;
+
+    fn select(context: &Self::Context, index: u32) {
+        if let Some(entry) = MenuEntry::try_from_index(index) {
+            context.view_switcher.switch_to_view(entry.view_id());
+        }
+    }
+}
+
+struct ProfileContext {
+    context: Arc<CallbackContext>,
+    // set once the view with this context was added
+    profile_view: Mutex<Weak<View<ProfileContext, ProfileRun>>>,
+}
+
+struct ProfileSubmenuItem;
+
+impl SubmenuItem for ProfileSubmenuItem {
+    type Context = ProfileContext;
+
+    fn select(context: &Self::Context, _: u32) {
+        let load = |path: &CStr| {
+            Profile::load(path).map_err(|error| match error {
+                ProfileError::Unreadable => String::from("Can't read the file"),
+                ProfileError::InvalidLine(line) => format!("Line {line} is no command"),
+            })
+        };
+        let kind = FileKind {
+            dir: PROFILE_DIR,
+            extension: PROFILE_EXTENSION,
+            invalid_header: c"Invalid Profile",
+        };
+        pick_and_start(kind, load, |profile| context.apply(profile));
+    }
+}
+
+impl ProfileContext {
+    /// Sends the commands of `profile` one after the other on the worker, the
+    /// profile view shows the progress and which of them the sensor
+    /// acknowledged.
+    ///
+    /// Returns whether it was started, see [`CallbackContext::start_job`].
+    fn apply(&self, profile: Profile) -> bool {
+        let view = self.profile_view.lock().clone();
+        self.context.start_job(move |context, cancel| {
+            let Some(view) = view.upgrade() else {
+                return;
+            };
+            *view.model().model = ProfileRun::new(&profile);
+            context.view_switcher.switch_to_view(PROFILE_VIEW_ID);
+
+            for cmd in profile.commands.iter().copied() {
+                if cancel.is_cancelled() {
+                    break;
+                }
+                let passed = context.send_command(cmd).is_answered();
+                view.model().model.finish_step(passed);
+            }
+        })
+    }
+}
+
+/// Scrolls the profile report.
+struct ProfileInput;
+
+impl ViewInputCallback for ProfileInput {
+    type Context = ProfileContext;
+
+    fn callback(event: InputEvent, context: &Self::Context) -> bool {
+        let rows = match (event.key, event.kind) {
+            (InputKey::Up, InputType::Short | InputType::Repeat) => -1,
+            (InputKey::Down, InputType::Short | InputType::Repeat) => 1,
+            _ => return false,
+        };
+        if let Some(view) = context.profile_view.lock().upgrade() {
+            view.model().model.scroll(rows);
+        }
+
+        true
+    }
+}
+
+/// Cancels the profile or script run a view shows and goes back to the menu.
+struct CancelRun;
+
+impl ViewNavigationCallback<CallbackContext> for CancelRun {
+    fn callback(context: &CallbackContext) -> u32 {
+        context.worker.cancel();
+        MENU_VIEW_ID
+    }
+}
+
+impl ViewNavigationCallback<ProfileContext> for CancelRun {
+    fn callback(context: &ProfileContext) -> u32 {
+        context.context.worker.cancel();
+        MENU_VIEW_ID
+    }
+}
+
+/// Tells about an error in a dialog with an OK button.
+fn show_error(dialogs: &Dialogs, header: &CStr, text: String) {
+    let text = CString::new(text).expect("no nul bytes in text");
+    let mut message = DialogMessage::new();
+    message.set_header(header, 64, 2, Align::Center, Align::Top);
+    message.set_text(&text, 64, 32, Align::Center, Align::Center);
+    message.set_buttons(None, Some(c"OK"), None);
+    dialogs.show_message(&message);
+}
+
+/// Where the files of a [`pick_and_start`] are and what they are called when
+/// they don't load.
+struct FileKind {
+    dir: &'static CStr,
+    extension: &'static CStr,
+    invalid_header: &'static CStr,
+}
+
+/// Lets the user pick a file of `kind`, loads it with `load` and hands it to
+/// `start`, which returns whether the worker took it.
+///
+/// Tells the user in a dialog if the file doesn't load, with the error text
+/// from `load`, or if the worker is still busy.
+fn pick_and_start<T>(
+    kind: FileKind,
+    load: impl FnOnce(&CStr) -> Result<T, String>,
+    start: impl FnOnce(T) -> bool,
+) {
+    // lets the browser start in the empty directory on first use
+    let storage = Storage::open();
+    storage.mkdir(DATA_DIR);
+    storage.mkdir(kind.dir);
+
+    let dialogs = Dialogs::open();
+    let Some(path) = dialogs.browse_file(kind.dir, kind.extension) else {
+        return;
+    };
+    match load(path.as_c_str()) {
+        Ok(loaded) if start(loaded) => (),
+        Ok(_) => show_error(&dialogs, c"Busy", String::from("Still sending, try again")),
+        Err(text) => show_error(&dialogs, kind.invalid_header, text),
+    }
+}
+
+struct ScriptContext {
+    context: Arc<CallbackContext>,
+    script_view: Weak<View<CallbackContext, ScriptRun>>,
+}
+
+struct ScriptSubmenuItem;
+
+impl SubmenuItem for ScriptSubmenuItem {
+    type Context = ScriptContext;
+
+    fn select(context: &Self::Context, _: u32) {
+        let load = |path: &CStr| {
+            Script::load(path).map_err(|error| match error {
+                ScriptLoadError::Unreadable => String::from("Can't read the file"),
+                ScriptLoadError::InvalidLine(line, error) => match error {
+                    ScriptError::UnknownCommand => format!("Line {line}: no command"),
+                    ScriptError::InvalidDuration => format!("Line {line}: bad duration"),
+                    ScriptError::MissingText => format!("Line {line}: text missing"),
+                },
+            })
+        };
+        let kind = FileKind {
+            dir: SCRIPT_DIR,
+            extension: SCRIPT_EXTENSION,
+            invalid_header: c"Invalid Script",
+        };
+        pick_and_start(kind, load, |script| context.run(script));
+    }
+}
+
+impl ScriptContext {
+    /// Runs `script` on the worker, see [`run_script`].
+    ///
+    /// Returns whether it was started, see [`CallbackContext::start_job`].
+    fn run(&self, script: Script) -> bool {
+        let view = self.script_view.clone();
+        self.context.start_job(move |context, cancel| {
+            if let Some(view) = view.upgrade() {
+                run_script(context, &view, &script, cancel);
+            }
+        })
+    }
+}
+
+/// Runs the steps of `script` until the first one that fails or until
+/// cancelled, the script view shows the progress and the transcript records
+/// every exchange.
+fn run_script(
+    context: &CallbackContext,
+    view: &View<CallbackContext, ScriptRun>,
+    script: &Script,
+    cancel: &Cancel,
+) {
+    *view.model().model = ScriptRun::new(script);
+    context.view_switcher.switch_to_view(SCRIPT_VIEW_ID);
+
+    let mut transcript = Transcript::open(script);
+    let responses = &context.responses;
+    // `expect` only looks at lines after the previous exchange
+    let mut since = responses.seq();
+    let mut state = ScriptState::Passed;
+
+    for (i, (number, step)) in script.steps().enumerate() {
+        if cancel.is_cancelled() {
+            state = ScriptState::Cancelled;
+            break;
+        }
+        let (entry, passed) = match step {
+            ScriptStep::Send(cmd) => {
+                let text = cmd.cmd().to_str().expect("valid utf-8").trim_end();
+                let entry = transcript.write('>', text);
+                view.model().model.set_last(&entry);
+                since = responses.seq();
+                match context.send_command(cmd) {
+                    CommandResult::Answered(line) => (transcript.write('<', &line), true),
+                    CommandResult::NoAnswer => (transcript.write('!', "no answer"), false),
+                    CommandResult::SensorLost => {
+                        (transcript.write('!', "sensor lost, run auto-baud"), false)
+                    }
+                }
+            }
+            ScriptStep::Wait(millis) => {
+                let entry = transcript.write('#', &format!("wait {millis}ms"));
+                view.model().model.set_last(&entry);
+                cancel.sleep(millis);
+                (entry, true)
+            }
+            ScriptStep::Expect(text) => {
+                let timeout = Duration::from_millis(EXPECT_TIMEOUT);
+                match responses.wait_for(since, timeout, |line| line.starts_with(text)) {
+                    Some(line) => {
+                        since = responses.seq();
+                        (transcript.write('<', &line), true)
+                    }
+                    None => (transcript.write('!', &format!("no {text}")), false),
+                }
+            }
+            ScriptStep::Log(text) => (transcript.write('#', text), true),
+        };
+
+        let run = view.model();
+        run.model.set_last(&entry);
+        run.model.step = i + 1;
+        if !passed {
+            state = ScriptState::Failed(number);
+            run.model.state = state;
+            break;
+        }
+    }
+
+    match state {
+        ScriptState::Failed(number) => transcript.write('!', &format!("failed in line {number}")),
+        ScriptState::Cancelled => transcript.write('!', "cancelled"),
+        _ => transcript.write('#', "passed"),
+    };
+    view.model().model.state = state;
+}
+
+struct CmdItemContext {
+    context: Arc<CallbackContext>,
+    main_view: Weak<View<CallbackContext, Data>>,
+    confirm_dialog: Weak<Mutex<DialogEx>>,
+    /// Command waiting for the confirmation.
+    pending: Mutex<Option<Command>>,
+}
+
 struct CmdSubmenuItem;
 
 impl SubmenuItem for CmdSubmenuItem {
-    type Context = CallbackContext;
+    type Context = CmdItemContext;
 
     fn select(context: &Self::Context, code: u32) {
-        let Some(cmd) = cmd::Command::try_from_code(code) else {
+        if code == CUSTOM_CMD_CODE {
+            context
+                .context
+                .view_switcher
+                .switch_to_view(CUSTOM_CMD_VIEW_ID);
+            return;
+        }
+        let Some(cmd) = Command::try_from_code(code) else {
+            return;
+        };
+
+        context.request(cmd.name(), cmd);
+    }
+}
+
+impl CmdItemContext {
+    /// Sends `cmd` like [`CmdItemContext::send`], but asks first if it
+    /// requires a confirmation.
+    fn request(&self, title: &CStr, cmd: Command) {
+        if !cmd.requires_confirmation() {
+            return self.send(title, cmd);
+        }
+
+        let Some(confirm_dialog) = self.confirm_dialog.upgrade() else {
             return;
         };
+        confirm_dialog
+            .lock()
+            .set_header(cmd.name(), 64, 2, Align::Center, Align::Top);
+        *self.pending.lock() = Some(cmd);
+        self.context.view_switcher.switch_to_view(CONFIRM_VIEW_ID);
+    }
+
+    /// Sends `cmd` like [`CmdItemContext::exchange`].
+    fn send(&self, title: &CStr, cmd: Command) {
+        self.exchange(title, move |context| context.send_command(cmd));
+    }
 
-        context.serial_handle.tx(cmd.cmd().as_bytes());
-        if let cmd::Command::BaudRate(baud_rate) = cmd {
-            context.serial_handle.set_br(baud_rate.rate() as u32);
+    /// Goes back to the main view and runs `exchange` on the worker, its
+    /// result titled `title` is shown there once it's in.
+    fn exchange(
+        &self,
+        title: &CStr,
+        exchange: impl FnOnce(&CallbackContext) -> CommandResult + Send + 'static,
+    ) {
+        let main_view = self.main_view.clone();
+        let job_title = CString::from(title);
+        let started = self.context.start_job(move |context, _| {
+            let title = job_title.as_c_str();
+            show_toast(&main_view, Toast::new(title, "Waiting for answer"));
+            let toast = match exchange(context) {
+                CommandResult::Answered(line) => Toast::new(title, &format!("OK: {line}")),
+                CommandResult::NoAnswer => Toast::new(title, "No answer"),
+                CommandResult::SensorLost => Toast::new(title, "Lost, run auto-baud"),
+            };
+            show_toast(&main_view, toast);
+        });
+        if !started {
+            show_toast(&self.main_view, Toast::new(title, "Busy, try again"));
         }
 
-        context.view_switcher.switch_to_view(MAIN_VIEW_ID);
+        self.context.view_switcher.switch_to_view(MAIN_VIEW_ID);
+    }
+}
+
+fn show_toast(main_view: &Weak<View<CallbackContext, Data>>, toast: Toast) {
+    if let Some(view) = main_view.upgrade() {
+        let data = view.model();
+        data.model.toast = Some(toast);
+    }
+}
+
+struct ConfirmDialog;
+
+impl DialogExResultCallback for ConfirmDialog {
+    type Context = CmdItemContext;
+
+    fn callback(result: DialogExResult, context: &Self::Context) {
+        let pending = context.pending.lock().take();
+        match (result, pending) {
+            (DialogExResult::Right, Some(cmd)) => context.send(cmd.name(), cmd),
+            _ => context.context.view_switcher.switch_to_view(CMD_VIEW_ID),
+        }
+    }
+}
+
+struct CustomCmdInput;
+
+impl TextInputResult for CustomCmdInput {
+    type Context = CmdItemContext;
+
+    fn result(context: &Self::Context, text: &CStr) {
+        let Ok(cmd_text) = text.to_str() else { return };
+
+        // known commands are sent as such, a baud rate change has to switch
+        // our side too
+        match Command::from_text(cmd_text) {
+            Some(cmd) => context.request(text, cmd),
+            None => {
+                let cmd_text = String::from(cmd_text);
+                context.exchange(text, move |context| {
+                    let session = CommandSession::new(&context.transport, &context.responses);
+                    session.send_text(&cmd_text)
+                });
+            }
+        }
+    }
+}
+
+struct SettingsItemContext {
+    context: Arc<CallbackContext>,
+    main_view: Weak<View<CallbackContext, Data>>,
+    setting: Setting,
+}
+
+struct SettingsItem;
+
+impl VariableItemChange for SettingsItem {
+    type Context = SettingsItemContext;
+
+    fn change(context: &Self::Context, item: &mut VariableItem) {
+        let SettingsItemContext {
+            context,
+            main_view,
+            setting,
+        } = context;
+
+        let index = item.current_value_index();
+        item.set_current_value_text(setting.value_name(index));
+
+        let settings = {
+            let mut settings = context.settings.lock();
+            setting.set_value_index(&mut settings, index);
+            *settings
+        };
+        settings.save();
+
+        // the rx thread reads the logging flag itself, port and baud rate are
+        // only used at the next start
+        match setting {
+            Setting::PollInterval => context.poller.set_interval(settings.poll_interval),
+            Setting::Units => {
+                let Some(view) = main_view.upgrade() else {
+                    return;
+                };
+                let data = view.model();
+                data.model.units = settings.units;
+            }
+            Setting::BaudRate | Setting::Port | Setting::Logging => (),
+        }
     }
 }
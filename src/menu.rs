@@ -0,0 +1,54 @@
+use core::{cell::Cell, ffi::CStr};
+
+use alloc::sync::Arc;
+
+use crate::safe::*;
+
+const MENU_VIEW_ID: u32 = 0;
+
+/// Shows a fullscreen submenu with `items` and blocks until one of them is
+/// selected.
+///
+/// Returns the index of the selected item, `None` if the user went back.
+pub fn choose(header: &CStr, items: &[(&CStr, u32)], selected: Option<u32>) -> Option<u32> {
+    let gui = Gui::open();
+    let mut view_dispatcher = ViewDispatcher::new(gui, ViewDispatcherType::Fullscreen);
+
+    struct Context {
+        view_switcher: ViewSwitcher,
+        choice: Cell<Option<u32>>,
+    }
+
+    let context = Arc::new(Context {
+        view_switcher: view_dispatcher.view_switcher(),
+        choice: Cell::new(None),
+    });
+
+    struct ChooseItem;
+
+    impl SubmenuItem for ChooseItem {
+        type Context = Context;
+
+        fn select(context: &Self::Context, index: u32) {
+            context.choice.set(Some(index));
+            context.view_switcher.stop();
+        }
+    }
+
+    let mut submenu = Submenu::new();
+    let submenu_view = submenu.as_mut_view();
+    submenu_view.set_previous_callback::<ViewNone>();
+    submenu.set_header(header);
+    for (label, index) in items {
+        submenu.add_item::<ChooseItem, _>(label, *index, Some(context.clone()));
+    }
+    if let Some(selected) = selected {
+        submenu.set_selected_item(selected);
+    }
+
+    view_dispatcher.add_submenu(submenu, MENU_VIEW_ID);
+    view_dispatcher.switch_to_view(MENU_VIEW_ID);
+    view_dispatcher.run();
+
+    context.choice.get()
+}
@@ -0,0 +1,83 @@
+use core::{
+    ffi::CStr,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+/// Selectable automatic poll intervals in seconds, 0 turns polling off.
+pub const POLL_INTERVALS: [(&CStr, u32); 5] = [
+    (c"Off", 0),
+    (c"5 s", 5),
+    (c"30 s", 30),
+    (c"1 min", 60),
+    (c"5 min", 5 * 60),
+];
+
+/// Counts down to the next automatic poll, ticked once a second by a timer.
+pub struct Poller {
+    interval: AtomicU32,
+    remaining: AtomicU32,
+    paused: AtomicBool,
+}
+
+impl Poller {
+    pub fn new() -> Self {
+        Self {
+            interval: AtomicU32::new(0),
+            remaining: AtomicU32::new(0),
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval.load(Ordering::Relaxed)
+    }
+
+    /// Sets the interval in seconds and starts counting down from it.
+    pub fn set_interval(&self, seconds: u32) {
+        self.interval.store(seconds, Ordering::Relaxed);
+        self.restart();
+    }
+
+    /// Starts counting down from the full interval again, e.g. after a manual
+    /// poll.
+    pub fn restart(&self) {
+        self.remaining.store(self.interval(), Ordering::Relaxed);
+    }
+
+    /// Keeps the countdown where it is, e.g. while commands are sent.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Seconds until the next poll, `None` if automatic polling is off.
+    pub fn remaining(&self) -> Option<u32> {
+        match self.interval() {
+            0 => None,
+            _ => Some(self.remaining.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Lets one second pass, returns whether a poll is due now.
+    pub fn tick(&self) -> bool {
+        let interval = self.interval();
+        if interval == 0 || self.paused.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let remaining = self.remaining.load(Ordering::Relaxed).saturating_sub(1);
+        match remaining {
+            0 => {
+                self.remaining.store(interval, Ordering::Relaxed);
+                true
+            }
+            remaining => {
+                self.remaining.store(remaining, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Configuration profiles on the SD card.
+//!
+//! A profile is a text file in [`PROFILE_DIR`] with one command per line in
+//! the text form the sensor understands, e.g. `c` or `b 3`, applied from top
+//! to bottom.
+//! Empty lines and lines starting with `#` are ignored.
+//! The file name without its extension is the profile's name.
+
+use core::{ffi::CStr, fmt::Write};
+
+use alloc::{ffi::CString, string::String, vec::Vec};
+use flipperzero::furi::string::FuriString;
+use rg15_protocol::Command;
+
+use crate::{run_view, safe::*, SCREEN_WIDTH};
+
+pub const PROFILE_DIR: &CStr = c"/ext/apps_data/rg15/profiles";
+pub const PROFILE_EXTENSION: &CStr = c".txt";
+
+/// Rows of steps shown in the report at once, the rest is scrolled to.
+const STEP_ROWS: usize = 4;
+const STEP_COLUMNS: usize = 3;
+
+pub struct Profile {
+    pub name: String,
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileError {
+    Unreadable,
+    /// The line with this number, starting at 1, is no command.
+    InvalidLine(u32),
+}
+
+impl Profile {
+    pub fn load(path: &CStr) -> Result<Self, ProfileError> {
+        let file = File::open(path, FileAccess::Read, FileOpen::Existing)
+            .ok_or(ProfileError::Unreadable)?;
+
+        let path = path.to_str().map_err(|_| ProfileError::Unreadable)?;
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let extension = PROFILE_EXTENSION.to_str().expect("valid utf-8");
+        let name = file_name.strip_suffix(extension).unwrap_or(file_name);
+
+        let mut commands = Vec::new();
+        let mut lines = file.lines();
+        let mut line = String::new();
+        let mut number = 0;
+        while lines.next_line(&mut line) {
+            number += 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let cmd = Command::from_text(line).ok_or(ProfileError::InvalidLine(number))?;
+            commands.push(cmd);
+        }
+
+        Ok(Self {
+            name: String::from(name),
+            commands,
+        })
+    }
+}
+
+struct Step {
+    // text form without the line ending
+    text: CString,
+    passed: Option<bool>,
+}
+
+/// Progress of applying a profile, and its report once done.
+#[derive(Default)]
+pub struct ProfileRun {
+    name: CString,
+    steps: Vec<Step>,
+    /// Row of steps shown at the top.
+    first_row: usize,
+}
+
+impl ProfileRun {
+    pub fn new(profile: &Profile) -> Self {
+        let steps = profile
+            .commands
+            .iter()
+            .map(|cmd| {
+                let text = cmd.cmd().to_str().expect("valid utf-8").trim_end();
+                Step {
+                    text: CString::new(text).expect("no nul bytes in commands"),
+                    passed: None,
+                }
+            })
+            .collect();
+        Self {
+            name: CString::new(profile.name.as_str()).unwrap_or_default(),
+            steps,
+            first_row: 0,
+        }
+    }
+
+    /// Records the result of the next step and scrolls to the one after it.
+    pub fn finish_step(&mut self, passed: bool) {
+        if let Some(step) = self.steps.iter_mut().find(|step| step.passed.is_none()) {
+            step.passed = Some(passed);
+        }
+
+        let row = self.finished().min(self.steps.len().saturating_sub(1)) / STEP_COLUMNS;
+        if row >= self.first_row + STEP_ROWS {
+            self.first_row = row + 1 - STEP_ROWS;
+        }
+    }
+
+    /// Scrolls the steps by `rows`, up if negative.
+    pub fn scroll(&mut self, rows: isize) {
+        let max = self.rows().saturating_sub(STEP_ROWS);
+        self.first_row = self.first_row.saturating_add_signed(rows).min(max);
+    }
+
+    fn rows(&self) -> usize {
+        self.steps.len().div_ceil(STEP_COLUMNS)
+    }
+
+    fn finished(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.passed.is_some())
+            .count()
+    }
+
+    fn passed(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.passed == Some(true))
+            .count()
+    }
+}
+
+pub struct ProfileView;
+
+impl ViewDrawCallback for ProfileView {
+    type Model = ProfileRun;
+
+    fn callback(canvas: &mut Canvas, run: &Self::Model) {
+        let total = run.steps.len();
+        let finished = run.finished();
+        let mut status = FuriString::new();
+        match finished < total {
+            true => write!(status, "Step {}/{}", finished + 1, total),
+            false => write!(status, "Passed {}/{}", run.passed(), total),
+        }
+        .expect("infallible");
+        let progress = (finished < total).then_some(finished as f32 / total as f32);
+        run_view::draw_header(canvas, &run.name, status.as_c_str(), progress);
+
+        let padding = run_view::PADDING;
+        // leaves room for the scrollbar on the right
+        let column_width = (SCREEN_WIDTH as usize - 2 * padding as usize) / STEP_COLUMNS;
+        let shown = run
+            .steps
+            .iter()
+            .skip(run.first_row * STEP_COLUMNS)
+            .take(STEP_ROWS * STEP_COLUMNS);
+        for (i, step) in shown.enumerate() {
+            let x = padding + (i % STEP_COLUMNS * column_width) as i32;
+            let y = 32 + (i / STEP_COLUMNS) as i32 * 10;
+            let result = match step.passed {
+                None => c"..",
+                Some(true) => c"ok",
+                Some(false) => c"FAIL",
+            };
+            canvas.draw_str(x, y, &step.text);
+            canvas.draw_str(x + 20, y, result);
+        }
+
+        let rows = run.rows();
+        if rows > STEP_ROWS {
+            let positions = rows - STEP_ROWS + 1;
+            canvas.elements_scrollbar_pos(SCREEN_WIDTH as i32, 24, 40, run.first_row, positions);
+        }
+    }
+}
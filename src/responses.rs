@@ -0,0 +1,88 @@
+use alloc::{collections::VecDeque, string::String};
+use flipperzero::furi::{
+    self,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use rg15_protocol::BaudRate;
+
+/// Number of lines kept for waiters that start looking a bit late.
+const CAPACITY: usize = 8;
+
+/// Time in ms the sensor gets to start answering a command.
+const RESPONSE_DELAY: u32 = 300;
+
+/// Longest line the RG-15 sends, including the line ending.
+pub const MAX_RESPONSE_LEN: u32 = 80;
+
+/// Lines received by the rx thread, shared with code that waits for the
+/// sensor to answer.
+pub struct Responses {
+    inbox: Mutex<Inbox>,
+}
+
+struct Inbox {
+    // sequence number of the newest line in `lines`
+    seq: u32,
+    lines: VecDeque<String>,
+}
+
+impl Responses {
+    pub fn new() -> Self {
+        Self {
+            inbox: Mutex::new(Inbox {
+                seq: 0,
+                lines: VecDeque::with_capacity(CAPACITY),
+            }),
+        }
+    }
+
+    pub fn push(&self, line: &str) {
+        let mut inbox = self.inbox.lock();
+        if inbox.lines.len() == CAPACITY {
+            inbox.lines.pop_front();
+        }
+        inbox.lines.push_back(String::from(line));
+        inbox.seq = inbox.seq.wrapping_add(1);
+    }
+
+    /// Marker to pass to [`Responses::wait_for`] to only look at lines
+    /// arriving after this call.
+    pub fn seq(&self) -> u32 {
+        self.inbox.lock().seq
+    }
+
+    /// Blocks until a line newer than `since` matches `predicate` or
+    /// `timeout` passed.
+    pub fn wait_for(
+        &self,
+        since: u32,
+        timeout: Duration,
+        mut predicate: impl FnMut(&str) -> bool,
+    ) -> Option<String> {
+        let start = Instant::now();
+        let mut seen = since;
+        loop {
+            {
+                let inbox = self.inbox.lock();
+                let new = inbox.seq.wrapping_sub(seen) as usize;
+                let skip = inbox.lines.len().saturating_sub(new);
+                let found = inbox.lines.iter().skip(skip).find(|line| predicate(line));
+                if let Some(line) = found {
+                    return Some(line.clone());
+                }
+                seen = inbox.seq;
+            }
+
+            if start.elapsed() >= timeout {
+                return None;
+            }
+            furi::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+/// How long to wait for a response line at `baud_rate`.
+pub fn response_timeout(baud_rate: BaudRate) -> Duration {
+    Duration::from_millis(RESPONSE_DELAY + baud_rate.transfer_millis(MAX_RESPONSE_LEN))
+}
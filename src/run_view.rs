@@ -0,0 +1,25 @@
+//! Drawing shared by the views of profile and script runs.
+
+use core::ffi::CStr;
+
+use crate::{safe::*, SCREEN_WIDTH};
+
+/// Space left and right of the texts.
+pub const PADDING: i32 = 4;
+/// Where the progress bar starts, right of the status.
+const PROGRESS_X: i32 = 56;
+
+/// Draws the `name` of what runs with its `status` below, and a progress bar
+/// right of the status while `progress` is `Some`.
+pub fn draw_header(canvas: &mut Canvas, name: &CStr, status: &CStr, progress: Option<f32>) {
+    canvas.draw_str(PADDING, 10, name);
+    canvas.draw_str(PADDING, 20, status);
+    if let Some(progress) = progress {
+        canvas.elements_progress_bar(
+            PROGRESS_X,
+            13,
+            SCREEN_WIDTH as usize - PROGRESS_X as usize - PADDING as usize,
+            progress,
+        );
+    }
+}
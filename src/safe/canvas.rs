@@ -6,7 +6,124 @@ pub struct Canvas {
     pub(super) data: NonNull<sys::Canvas>,
 }
 
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Align {
+    Left = sys::Align_AlignLeft,
+    Right = sys::Align_AlignRight,
+    Top = sys::Align_AlignTop,
+    Bottom = sys::Align_AlignBottom,
+    Center = sys::Align_AlignCenter,
+}
+
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Color {
+    White = sys::Color_ColorWhite,
+    Black = sys::Color_ColorBlack,
+    Xor = sys::Color_ColorXOR,
+}
+
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Font {
+    Primary = sys::Font_FontPrimary,
+    Secondary = sys::Font_FontSecondary,
+    Keyboard = sys::Font_FontKeyboard,
+    BigNumbers = sys::Font_FontBigNumbers,
+}
+
+/// An icon from the firmware's assets.
+#[allow(unused)]
+#[derive(Clone, Copy)]
+pub struct Icon {
+    data: &'static sys::Icon,
+}
+
+impl Icon {
+    #[allow(unused)]
+    pub const fn new(data: &'static sys::Icon) -> Self {
+        Self { data }
+    }
+}
+
+/// A monochrome bitmap in XBM format: rows of `width` bits, each padded to
+/// whole bytes with the leftmost pixel in the lowest bit.
+#[derive(Debug, Clone, Copy)]
+pub struct Xbm<'b> {
+    width: usize,
+    height: usize,
+    data: &'b [u8],
+}
+
+impl<'b> Xbm<'b> {
+    /// Panics if `data` is too short for `width` and `height`, at compile
+    /// time for constants.
+    pub const fn new(width: usize, height: usize, data: &'b [u8]) -> Self {
+        assert!(data.len() >= width.div_ceil(8) * height);
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    #[allow(unused)]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[allow(unused)]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+}
+
+// the whole drawing API is wrapped, what nothing draws with yet is marked
 impl Canvas {
+    /// Width of the drawable area in pixels.
+    #[allow(unused)]
+    pub fn width(&self) -> usize {
+        unsafe { sys::canvas_width(self.data.as_ptr()) }
+    }
+
+    /// Height of the drawable area in pixels.
+    #[allow(unused)]
+    pub fn height(&self) -> usize {
+        unsafe { sys::canvas_height(self.data.as_ptr()) }
+    }
+
+    /// Clears the whole canvas to white.
+    #[allow(unused)]
+    pub fn clear(&mut self) {
+        unsafe {
+            sys::canvas_clear(self.data.as_ptr());
+        }
+    }
+
+    #[allow(unused)]
+    pub fn set_color(&mut self, color: Color) {
+        unsafe {
+            sys::canvas_set_color(self.data.as_ptr(), color as u8);
+        }
+    }
+
+    #[allow(unused)]
+    pub fn set_font(&mut self, font: Font) {
+        unsafe {
+            sys::canvas_set_font(self.data.as_ptr(), font as u8);
+        }
+    }
+
+    /// Height of the current font's capital letters in pixels.
+    #[allow(unused)]
+    pub fn current_font_height(&self) -> usize {
+        unsafe { sys::canvas_current_font_height(self.data.as_ptr()) }
+    }
+
     pub fn elements_button_left(&mut self, s: &CStr) {
         unsafe {
             sys::elements_button_left(self.data.as_ptr(), s.as_ptr());
@@ -19,9 +136,48 @@ impl Canvas {
         }
     }
 
-    pub fn draw_dot(&mut self, x: i32, y: i32) {
+    pub fn elements_progress_bar(&mut self, x: i32, y: i32, width: usize, progress: f32) {
         unsafe {
-            sys::canvas_draw_dot(self.data.as_ptr(), x, y);
+            sys::elements_progress_bar(self.data.as_ptr(), x, y, width, progress);
+        }
+    }
+
+    /// Scrollbar left of `x` at `pos` of `total` positions.
+    pub fn elements_scrollbar_pos(
+        &mut self,
+        x: i32,
+        y: i32,
+        height: usize,
+        pos: usize,
+        total: usize,
+    ) {
+        unsafe {
+            sys::elements_scrollbar_pos(self.data.as_ptr(), x, y, height, pos, total);
+        }
+    }
+
+    pub fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        unsafe {
+            sys::canvas_draw_line(self.data.as_ptr(), x1, y1, x2, y2);
+        }
+    }
+
+    pub fn draw_frame(&mut self, x: i32, y: i32, width: usize, height: usize) {
+        unsafe {
+            sys::canvas_draw_frame(self.data.as_ptr(), x, y, width, height);
+        }
+    }
+
+    #[allow(unused)]
+    pub fn draw_rframe(&mut self, x: i32, y: i32, width: usize, height: usize, radius: usize) {
+        unsafe {
+            sys::canvas_draw_rframe(self.data.as_ptr(), x, y, width, height, radius);
+        }
+    }
+
+    pub fn draw_box(&mut self, x: i32, y: i32, width: usize, height: usize) {
+        unsafe {
+            sys::canvas_draw_box(self.data.as_ptr(), x, y, width, height);
         }
     }
 
@@ -31,6 +187,42 @@ impl Canvas {
         }
     }
 
+    /// Draws the outline of a circle around `x`, `y`.
+    #[allow(unused)]
+    pub fn draw_circle(&mut self, x: i32, y: i32, radius: usize) {
+        unsafe {
+            sys::canvas_draw_circle(self.data.as_ptr(), x, y, radius);
+        }
+    }
+
+    /// Draws a filled circle around `x`, `y`.
+    #[allow(unused)]
+    pub fn draw_disc(&mut self, x: i32, y: i32, radius: usize) {
+        unsafe {
+            sys::canvas_draw_disc(self.data.as_ptr(), x, y, radius);
+        }
+    }
+
+    pub fn draw_xbm(&mut self, x: i32, y: i32, xbm: Xbm) {
+        unsafe {
+            sys::canvas_draw_xbm(
+                self.data.as_ptr(),
+                x,
+                y,
+                xbm.width,
+                xbm.height,
+                xbm.data.as_ptr(),
+            );
+        }
+    }
+
+    #[allow(unused)]
+    pub fn draw_icon(&mut self, x: i32, y: i32, icon: Icon) {
+        unsafe {
+            sys::canvas_draw_icon(self.data.as_ptr(), x, y, icon.data);
+        }
+    }
+
     pub fn invert_color(&mut self) {
         unsafe {
             sys::canvas_invert_color(self.data.as_ptr());
@@ -46,4 +238,38 @@ impl Canvas {
             sys::canvas_draw_str(self.data.as_ptr(), x, y, s.as_ptr());
         }
     }
+
+    /// Draws `s` with `x`, `y` at the given edges or center of the text.
+    #[allow(unused)]
+    pub fn draw_str_aligned(
+        &mut self,
+        x: i32,
+        y: i32,
+        horizontal: Align,
+        vertical: Align,
+        s: &CStr,
+    ) {
+        unsafe {
+            sys::canvas_draw_str_aligned(
+                self.data.as_ptr(),
+                x,
+                y,
+                horizontal as u8,
+                vertical as u8,
+                s.as_ptr(),
+            );
+        }
+    }
+
+    /// Draws a single character of the current font, nothing for characters
+    /// outside the Basic Multilingual Plane.
+    #[allow(unused)]
+    pub fn draw_glyph(&mut self, x: i32, y: i32, ch: char) {
+        let Ok(ch) = u16::try_from(u32::from(ch)) else {
+            return;
+        };
+        unsafe {
+            sys::canvas_draw_glyph(self.data.as_ptr(), x, y, ch);
+        }
+    }
 }
@@ -0,0 +1,130 @@
+use alloc::sync::Arc;
+use core::{
+    any::Any,
+    ffi::{c_void, CStr},
+    mem::ManuallyDrop,
+    ptr::{self, NonNull},
+};
+use flipperzero_sys as sys;
+
+use super::{Align, View};
+
+/// A dialog view with a header, a text and up to three buttons.
+///
+/// Only pointers to the texts are stored, so they have to be `'static`.
+pub struct DialogEx {
+    pub(super) data: NonNull<sys::DialogEx>,
+    // this view is purely a reference to the dialog here, so don't try to drop it
+    view: ManuallyDrop<View>,
+    context: Option<Arc<dyn Any>>,
+}
+
+unsafe impl Send for DialogEx {}
+unsafe impl Sync for DialogEx {}
+
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DialogExResult {
+    Left = sys::DialogExResult_DialogExResultLeft,
+    Center = sys::DialogExResult_DialogExResultCenter,
+    Right = sys::DialogExResult_DialogExResultRight,
+    PressCenter = sys::DialogExResult_DialogExPressCenter,
+    ReleaseCenter = sys::DialogExResult_DialogExReleaseCenter,
+}
+
+impl DialogExResult {
+    fn from_raw(result: sys::DialogExResult) -> Option<Self> {
+        Some(match result {
+            sys::DialogExResult_DialogExResultLeft => DialogExResult::Left,
+            sys::DialogExResult_DialogExResultCenter => DialogExResult::Center,
+            sys::DialogExResult_DialogExResultRight => DialogExResult::Right,
+            sys::DialogExResult_DialogExPressCenter => DialogExResult::PressCenter,
+            sys::DialogExResult_DialogExReleaseCenter => DialogExResult::ReleaseCenter,
+            _ => return None,
+        })
+    }
+}
+
+impl DialogEx {
+    pub fn new() -> Self {
+        unsafe {
+            let data = sys::dialog_ex_alloc();
+            let view = sys::dialog_ex_get_view(data);
+            let view = View::from_raw(view);
+            let data = NonNull::new_unchecked(data);
+            Self {
+                data,
+                view,
+                context: None,
+            }
+        }
+    }
+
+    pub fn set_header(&mut self, text: &'static CStr, x: u8, y: u8, h: Align, v: Align) {
+        unsafe {
+            sys::dialog_ex_set_header(self.data.as_ptr(), text.as_ptr(), x, y, h as u8, v as u8);
+        }
+    }
+
+    pub fn set_text(&mut self, text: &'static CStr, x: u8, y: u8, h: Align, v: Align) {
+        unsafe {
+            sys::dialog_ex_set_text(self.data.as_ptr(), text.as_ptr(), x, y, h as u8, v as u8);
+        }
+    }
+
+    /// Buttons without a label are hidden.
+    pub fn set_buttons(
+        &mut self,
+        left: Option<&'static CStr>,
+        center: Option<&'static CStr>,
+        right: Option<&'static CStr>,
+    ) {
+        let label = |label: Option<&CStr>| label.map_or(ptr::null(), CStr::as_ptr);
+        unsafe {
+            sys::dialog_ex_set_left_button_text(self.data.as_ptr(), label(left));
+            sys::dialog_ex_set_center_button_text(self.data.as_ptr(), label(center));
+            sys::dialog_ex_set_right_button_text(self.data.as_ptr(), label(right));
+        }
+    }
+
+    pub fn set_result_callback<C: DialogExResultCallback>(&mut self, context: Arc<C::Context>) {
+        unsafe {
+            sys::dialog_ex_set_context(self.data.as_ptr(), Arc::as_ptr(&context).cast_mut().cast());
+            sys::dialog_ex_set_result_callback(self.data.as_ptr(), Some(C::__callback));
+        }
+        self.context = Some(context);
+    }
+
+    pub fn as_view(&self) -> &View {
+        &self.view
+    }
+
+    pub fn as_mut_view(&mut self) -> &mut View {
+        &mut self.view
+    }
+}
+
+pub trait DialogExResultCallback {
+    type Context: Any;
+
+    fn callback(result: DialogExResult, context: &Self::Context);
+
+    #[doc(hidden)]
+    unsafe extern "C" fn __callback(result: sys::DialogExResult, context: *mut c_void) {
+        let Some(result) = DialogExResult::from_raw(result) else {
+            return;
+        };
+        let context: *const Self::Context = context.cast_const().cast();
+        let context: &Self::Context = context.as_ref_unchecked();
+        Self::callback(result, context);
+    }
+}
+
+impl Drop for DialogEx {
+    fn drop(&mut self) {
+        unsafe {
+            sys::dialog_ex_free(self.data.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use core::{
+    ffi::CStr,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ptr::{self, NonNull},
+};
+
+use flipperzero::furi::string::FuriString;
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::UnsafeRecord;
+
+use super::Align;
+
+pub struct Dialogs {
+    pub(super) data: UnsafeRecord<sys::DialogsApp>,
+}
+
+impl Dialogs {
+    pub fn open() -> Self {
+        unsafe {
+            Self {
+                data: UnsafeRecord::open(c"dialogs".as_ptr()),
+            }
+        }
+    }
+
+    /// Shows the file browser starting in `dir` with only files ending in
+    /// `extension`, blocks until the user picked one or went back.
+    pub fn browse_file(&self, dir: &CStr, extension: &CStr) -> Option<FuriString> {
+        let mut path = FuriString::from(dir);
+        unsafe {
+            let mut options = MaybeUninit::<sys::DialogsFileBrowserOptions>::uninit();
+            sys::dialog_file_browser_set_basic_options(
+                options.as_mut_ptr(),
+                extension.as_ptr(),
+                ptr::null(),
+            );
+            let options = options.assume_init();
+
+            // the browser reads the start path from and writes the result to
+            // the same string
+            let path_ptr = path.as_mut_ptr();
+            sys::dialog_file_browser_show(self.data.as_ptr(), path_ptr, path_ptr, &options)
+        }
+        .then_some(path)
+    }
+
+    /// Shows `message` and blocks until a button or back was pressed.
+    pub fn show_message(&self, message: &DialogMessage) -> DialogMessageButton {
+        let button = unsafe { sys::dialog_message_show(self.data.as_ptr(), message.data.as_ptr()) };
+        match button {
+            sys::DialogMessageButton_DialogMessageButtonLeft => DialogMessageButton::Left,
+            sys::DialogMessageButton_DialogMessageButtonCenter => DialogMessageButton::Center,
+            sys::DialogMessageButton_DialogMessageButtonRight => DialogMessageButton::Right,
+            _ => DialogMessageButton::Back,
+        }
+    }
+}
+
+/// A modal message with up to three buttons, shown by [`Dialogs::show_message`].
+///
+/// Only pointers to the texts are stored, so they have to outlive the message.
+pub struct DialogMessage<'t> {
+    pub(super) data: NonNull<sys::DialogMessage>,
+    _texts: PhantomData<&'t CStr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogMessageButton {
+    Back,
+    Left,
+    Center,
+    Right,
+}
+
+impl<'t> DialogMessage<'t> {
+    pub fn new() -> Self {
+        unsafe {
+            Self {
+                data: NonNull::new_unchecked(sys::dialog_message_alloc()),
+                _texts: PhantomData,
+            }
+        }
+    }
+
+    pub fn set_header(&mut self, text: &'t CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        unsafe {
+            sys::dialog_message_set_header(
+                self.data.as_ptr(),
+                text.as_ptr(),
+                x,
+                y,
+                horizontal as u8,
+                vertical as u8,
+            );
+        }
+    }
+
+    pub fn set_text(&mut self, text: &'t CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        unsafe {
+            sys::dialog_message_set_text(
+                self.data.as_ptr(),
+                text.as_ptr(),
+                x,
+                y,
+                horizontal as u8,
+                vertical as u8,
+            );
+        }
+    }
+
+    /// Buttons without a label are hidden.
+    pub fn set_buttons(
+        &mut self,
+        left: Option<&'t CStr>,
+        center: Option<&'t CStr>,
+        right: Option<&'t CStr>,
+    ) {
+        let label = |label: Option<&CStr>| label.map_or(ptr::null(), CStr::as_ptr);
+        unsafe {
+            sys::dialog_message_set_buttons(
+                self.data.as_ptr(),
+                label(left),
+                label(center),
+                label(right),
+            );
+        }
+    }
+}
+
+impl Drop for DialogMessage<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::dialog_message_free(self.data.as_ptr());
+        }
+    }
+}
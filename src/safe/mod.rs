@@ -1,15 +1,29 @@
 mod canvas;
+mod dialog_ex;
+mod dialogs;
 mod gui;
+mod rtc;
 mod serial_handle;
+mod storage;
 mod submenu;
+mod text_input;
+mod timer;
+mod variable_item_list;
 mod view;
 mod view_dispatcher;
 mod widget;
 
 pub use canvas::*;
+pub use dialog_ex::*;
+pub use dialogs::*;
 pub use gui::*;
+pub use rtc::*;
 pub use serial_handle::*;
+pub use storage::*;
 pub use submenu::*;
+pub use text_input::*;
+pub use timer::*;
+pub use variable_item_list::*;
 pub use view::*;
 pub use view_dispatcher::*;
 pub use widget::*;
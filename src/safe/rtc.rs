@@ -0,0 +1,46 @@
+use core::fmt;
+
+use flipperzero_sys as sys;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Reads the current time from the real time clock.
+    pub fn now() -> Self {
+        unsafe {
+            let mut datetime: sys::DateTime = core::mem::zeroed();
+            sys::furi_hal_rtc_get_datetime(&mut datetime);
+            Self {
+                year: datetime.year,
+                month: datetime.month,
+                day: datetime.day,
+                hour: datetime.hour,
+                minute: datetime.minute,
+                second: datetime.second,
+            }
+        }
+    }
+
+    pub fn same_day(&self, other: &Self) -> bool {
+        (self.year, self.month, self.day) == (other.year, other.month, other.day)
+    }
+}
+
+/// Formats as ISO 8601, e.g. `2024-10-20T13:37:00`.
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
@@ -5,16 +5,56 @@ use core::{
     mem::{self, ManuallyDrop},
     num::NonZeroUsize,
     ptr::{self, NonNull},
+    sync::atomic::{AtomicU32, Ordering},
 };
 use flipperzero::{furi, println};
 use flipperzero_sys as sys;
 
 pub struct SerialHandle<M> {
     pub(super) data: NonNull<sys::FuriHalSerialHandle>,
-    pub(super) context: Option<Arc<furi::stream_buffer::Sender>>,
+    pub(super) context: Option<Arc<RxContext>>,
+    pub(super) baud_rate: AtomicU32,
     _phantom: PhantomData<M>,
 }
 
+// the firmware takes calls from any thread, though sends from several
+// threads at once mix their bytes
+unsafe impl<M> Send for SerialHandle<M> {}
+unsafe impl<M> Sync for SerialHandle<M> {}
+
+/// Bytes the interrupt collects before passing them on.
+const RX_CHUNK_LEN: usize = 16;
+
+pub(super) struct RxContext {
+    sender: furi::stream_buffer::Sender,
+    errors: Arc<RxErrors>,
+}
+
+/// Receive errors counted by the interrupt since reception started.
+#[derive(Debug, Default)]
+pub struct RxErrors {
+    frame: AtomicU32,
+    noise: AtomicU32,
+    overrun: AtomicU32,
+}
+
+impl RxErrors {
+    /// Bits that didn't look like a valid stop bit, usually a baud mismatch.
+    pub fn frame(&self) -> u32 {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    /// Bits sampled inconsistently, usually bad wiring or interference.
+    pub fn noise(&self) -> u32 {
+        self.noise.load(Ordering::Relaxed)
+    }
+
+    /// Bytes lost because the previous one wasn't read in time.
+    pub fn overrun(&self) -> u32 {
+        self.overrun.load(Ordering::Relaxed)
+    }
+}
+
 pub mod serial_marker {
     pub struct Uninitialized;
     pub struct Initialized;
@@ -37,6 +77,7 @@ impl SerialHandle<serial_marker::Uninitialized> {
             Some(Self {
                 data,
                 context: None,
+                baud_rate: AtomicU32::new(0),
                 _phantom: PhantomData,
             })
         }
@@ -45,6 +86,7 @@ impl SerialHandle<serial_marker::Uninitialized> {
     pub fn init(self, baud: u32) -> SerialHandle<serial_marker::Initialized> {
         unsafe {
             sys::furi_hal_serial_init(self.data.as_ptr(), baud);
+            self.baud_rate.store(baud, Ordering::Relaxed);
             mem::transmute(self)
         }
     }
@@ -56,7 +98,10 @@ impl SerialHandle<serial_marker::Initialized> {
         let stream_buffer = furi::stream_buffer::StreamBuffer::new(size, 1);
         let (tx, rx) = stream_buffer.into_stream();
 
-        let context = Arc::new(tx);
+        let context = Arc::new(RxContext {
+            sender: tx,
+            errors: Arc::new(RxErrors::default()),
+        });
         let context_ptr = Arc::as_ptr(&context).cast_mut().cast();
         self.context = Some(context);
 
@@ -84,11 +129,21 @@ impl SerialHandle<serial_marker::Initialized> {
         unsafe {
             sys::furi_hal_serial_set_br(self.data.as_ptr(), baud_rate);
         }
+        self.baud_rate.store(baud_rate, Ordering::Relaxed);
+    }
+
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate.load(Ordering::Relaxed)
+    }
+
+    /// `None` before [`SerialHandle::async_rx_start`], errors are only counted
+    /// if it was called with `report_errors`.
+    pub fn rx_errors(&self) -> Option<Arc<RxErrors>> {
+        self.context.as_ref().map(|context| context.errors.clone())
     }
 }
 
 impl SerialHandle<serial_marker::Interrupted> {
-    #[allow(unused)]
     pub fn rx_available(&self) -> bool {
         unsafe { sys::furi_hal_serial_async_rx_available(self.data.as_ptr()) }
     }
@@ -98,7 +153,9 @@ impl SerialHandle<serial_marker::Interrupted> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// The interrupt reports all events that occurred at once, so these are flags.
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum SerialRxEvent {
     Data = sys::FuriHalSerialRxEvent_FuriHalSerialRxEventData,
@@ -108,6 +165,15 @@ pub enum SerialRxEvent {
     OverrunError = sys::FuriHalSerialRxEvent_FuriHalSerialRxEventOverrunError,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialRxEvents(u8);
+
+impl SerialRxEvents {
+    pub fn contains(self, event: SerialRxEvent) -> bool {
+        self.0 & event as u8 != 0
+    }
+}
+
 unsafe extern "C" fn raw_callback(
     handle: *mut sys::FuriHalSerialHandle,
     event: sys::FuriHalSerialRxEvent,
@@ -120,36 +186,55 @@ unsafe extern "C" fn raw_callback(
     let handle = SerialHandle::<serial_marker::Interrupted> {
         data: handle,
         context: None,
+        baud_rate: AtomicU32::new(0),
         _phantom: PhantomData,
     };
     let handle = ManuallyDrop::new(handle);
 
-    let event = match event {
-        1 => SerialRxEvent::Data,
-        2 => SerialRxEvent::Idle,
-        4 => SerialRxEvent::FrameError,
-        8 => SerialRxEvent::NoiseError,
-        16 => SerialRxEvent::OverrunError,
-        _ => unreachable!("enum is defined by these values only"),
-    };
+    let events = SerialRxEvents(event);
 
-    let context: *const furi::stream_buffer::Sender = context.cast_const().cast();
-    let context: &furi::stream_buffer::Sender = context.as_ref_unchecked();
+    let context: *const RxContext = context.cast_const().cast();
+    let context: &RxContext = context.as_ref_unchecked();
 
-    callback(&handle, event, context);
+    callback(&handle, events, context);
 }
 
 #[inline]
 fn callback(
     handle: &SerialHandle<serial_marker::Interrupted>,
-    event: SerialRxEvent,
-    sender: &furi::stream_buffer::Sender,
+    events: SerialRxEvents,
+    context: &RxContext,
 ) {
-    if event != SerialRxEvent::Data {
+    let errors = &context.errors;
+    for (event, counter) in [
+        (SerialRxEvent::FrameError, &errors.frame),
+        (SerialRxEvent::NoiseError, &errors.noise),
+        (SerialRxEvent::OverrunError, &errors.overrun),
+    ] {
+        if events.contains(event) {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    if !events.contains(SerialRxEvent::Data) {
         return;
     }
-    let data = handle.rx();
-    sender.send(&[data]);
+
+    // the hardware may have buffered more than one byte, waking the reader
+    // once for all of them is much cheaper than once per byte
+    let mut bytes = [0u8; RX_CHUNK_LEN];
+    let mut len = 0;
+    while handle.rx_available() {
+        bytes[len] = handle.rx();
+        len += 1;
+        if len == RX_CHUNK_LEN {
+            context.sender.send(&bytes);
+            len = 0;
+        }
+    }
+    if len > 0 {
+        context.sender.send(&bytes[..len]);
+    }
 }
 
 impl<M> Drop for SerialHandle<M> {
@@ -160,5 +245,11 @@ impl<M> Drop for SerialHandle<M> {
             sys::furi_hal_serial_deinit(self.data.as_ptr());
             sys::furi_hal_serial_control_release(self.data.as_ptr());
         }
+
+        // the interrupt is stopped, so this is the only sender left, wake up a
+        // reader waiting for data to let it notice the handle is gone
+        if let Some(context) = self.context.as_ref() {
+            context.sender.send(b"\n");
+        }
     }
 }
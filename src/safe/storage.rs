@@ -0,0 +1,175 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    ffi::{c_void, CStr},
+    ptr::NonNull,
+};
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::UnsafeRecord;
+
+pub struct Storage {
+    pub(super) data: UnsafeRecord<sys::Storage>,
+}
+
+unsafe impl Send for Storage {}
+unsafe impl Sync for Storage {}
+
+#[allow(unused)] // for completeness we have all variants
+#[repr(u8)]
+pub enum FileAccess {
+    Read = sys::FS_AccessMode_FSAM_READ,
+    Write = sys::FS_AccessMode_FSAM_WRITE,
+    ReadWrite = sys::FS_AccessMode_FSAM_READ_WRITE,
+}
+
+#[allow(unused)] // for completeness we have all variants
+#[repr(u8)]
+pub enum FileOpen {
+    Existing = sys::FS_OpenMode_FSOM_OPEN_EXISTING,
+    Always = sys::FS_OpenMode_FSOM_OPEN_ALWAYS,
+    Append = sys::FS_OpenMode_FSOM_OPEN_APPEND,
+    CreateNew = sys::FS_OpenMode_FSOM_CREATE_NEW,
+    CreateAlways = sys::FS_OpenMode_FSOM_CREATE_ALWAYS,
+}
+
+impl Storage {
+    pub fn open() -> Self {
+        unsafe {
+            Self {
+                data: UnsafeRecord::open(c"storage".as_ptr()),
+            }
+        }
+    }
+
+    /// Creates the directory at `path`, parent directories must exist.
+    ///
+    /// Returns `true` if the directory exists afterwards.
+    pub fn mkdir(&self, path: &CStr) -> bool {
+        unsafe { sys::storage_simply_mkdir(self.data.as_ptr(), path.as_ptr()) }
+    }
+}
+
+pub struct File {
+    pub(super) data: NonNull<sys::File>,
+    // keeps the storage record open as long as the file lives
+    _storage: Storage,
+}
+
+unsafe impl Send for File {}
+
+impl File {
+    pub fn open(path: &CStr, access: FileAccess, open: FileOpen) -> Option<Self> {
+        let storage = Storage::open();
+        unsafe {
+            let data = NonNull::new_unchecked(sys::storage_file_alloc(storage.data.as_ptr()));
+            let file = Self {
+                data,
+                _storage: storage,
+            };
+            // a file that failed to open still has to be freed, which `Drop` does
+            match sys::storage_file_open(
+                file.data.as_ptr(),
+                path.as_ptr(),
+                access as u8,
+                open as u8,
+            ) {
+                true => Some(file),
+                false => None,
+            }
+        }
+    }
+
+    /// Writes `buf` and returns how many bytes were actually written.
+    pub fn write(&mut self, buf: &[u8]) -> usize {
+        unsafe {
+            sys::storage_file_write(self.data.as_ptr(), buf.as_ptr().cast::<c_void>(), buf.len())
+        }
+    }
+
+    /// Reads into `buf` and returns how many bytes were read, `0` at the end
+    /// of the file.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        unsafe {
+            sys::storage_file_read(
+                self.data.as_ptr(),
+                buf.as_mut_ptr().cast::<c_void>(),
+                buf.len(),
+            )
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        unsafe { sys::storage_file_size(self.data.as_ptr()) }
+    }
+
+    /// Flushes buffered writes to the card.
+    pub fn sync(&mut self) -> bool {
+        unsafe { sys::storage_file_sync(self.data.as_ptr()) }
+    }
+
+    pub fn lines(self) -> FileLines {
+        FileLines {
+            file: self,
+            buf: [0; 256],
+            pos: 0,
+            len: 0,
+            line: Vec::new(),
+        }
+    }
+}
+
+/// Reads a file line by line without loading it as a whole.
+pub struct FileLines {
+    file: File,
+    buf: [u8; 256],
+    pos: usize,
+    len: usize,
+    line: Vec<u8>,
+}
+
+impl FileLines {
+    /// Replaces `line` with the next line without its line ending, returns
+    /// `false` at the end of the file.
+    ///
+    /// Invalid UTF-8 is replaced instead of failing the whole file.
+    pub fn next_line(&mut self, line: &mut String) -> bool {
+        self.line.clear();
+        loop {
+            if self.pos == self.len {
+                self.pos = 0;
+                self.len = self.file.read(&mut self.buf);
+                if self.len == 0 {
+                    if self.line.is_empty() {
+                        return false;
+                    }
+                    break;
+                }
+            }
+
+            let byte = self.buf[self.pos];
+            self.pos += 1;
+            if byte == b'\n' {
+                break;
+            }
+            self.line.push(byte);
+        }
+
+        line.clear();
+        line.push_str(&String::from_utf8_lossy(&self.line));
+        if line.ends_with('\r') {
+            line.pop();
+        }
+        true
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        unsafe {
+            if sys::storage_file_is_open(self.data.as_ptr()) {
+                sys::storage_file_close(self.data.as_ptr());
+            }
+            sys::storage_file_free(self.data.as_ptr());
+        }
+    }
+}
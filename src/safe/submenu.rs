@@ -1,5 +1,6 @@
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 use core::{
+    any::Any,
     ffi::{c_void, CStr},
     mem::ManuallyDrop,
     ptr::{self, NonNull},
@@ -12,6 +13,8 @@ pub struct Submenu {
     pub(super) data: NonNull<sys::Submenu>,
     // this view is purely a reference to the submenu here, so don't try to drop it
     view: ManuallyDrop<View>,
+    // the items only have pointers into these
+    contexts: Vec<Arc<dyn Any>>,
 }
 
 impl Submenu {
@@ -19,14 +22,13 @@ impl Submenu {
         unsafe {
             let data = sys::submenu_alloc();
             let view = sys::submenu_get_view(data);
-            let view = View {
-                data: NonNull::new_unchecked(view),
-                context: None,
-                has_model: false,
-            };
-            let view = ManuallyDrop::new(view);
+            let view = View::from_raw(view);
             let data = NonNull::new_unchecked(data);
-            Self { data, view }
+            Self {
+                data,
+                view,
+                contexts: Vec::new(),
+            }
         }
     }
 
@@ -36,16 +38,18 @@ impl Submenu {
     {
         let submenu = self.data.as_ptr();
         let label = label.as_ptr();
-        // FIXME: keep Arcs stored somewhere instead of dropping them here
         unsafe {
             match context {
-                Some(context) => sys::submenu_add_item(
-                    submenu,
-                    label,
-                    index,
-                    Some(I::__select),
-                    Arc::as_ptr(&context).cast_mut().cast(),
-                ),
+                Some(context) => {
+                    sys::submenu_add_item(
+                        submenu,
+                        label,
+                        index,
+                        Some(I::__select),
+                        Arc::as_ptr(&context).cast_mut().cast(),
+                    );
+                    self.contexts.push(context);
+                }
                 None => sys::submenu_add_item(submenu, label, index, None, ptr::null_mut()),
             }
         };
@@ -73,7 +77,7 @@ impl Submenu {
 }
 
 pub trait SubmenuItem {
-    type Context;
+    type Context: Any;
 
     fn select(context: &Self::Context, index: u32);
 
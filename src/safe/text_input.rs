@@ -0,0 +1,107 @@
+use alloc::{boxed::Box, sync::Arc, vec};
+use core::{
+    any::Any,
+    ffi::{c_char, c_void, CStr},
+    mem::ManuallyDrop,
+    ptr::NonNull,
+};
+use flipperzero_sys as sys;
+
+use super::View;
+
+/// On-screen keyboard to enter a line of text.
+pub struct TextInput {
+    pub(super) data: NonNull<sys::TextInput>,
+    // this view is purely a reference to the text input here, so don't try to drop it
+    view: ManuallyDrop<View>,
+    // the keyboard writes directly into this
+    buffer: Box<[u8]>,
+    // the result callback only has a pointer into this
+    context: Option<Box<dyn Any>>,
+}
+
+struct ResultContext<C> {
+    buffer: *const c_char,
+    context: Arc<C>,
+}
+
+impl TextInput {
+    /// Creates a text input for texts of up to `max_len` bytes.
+    pub fn new(max_len: usize) -> Self {
+        unsafe {
+            let data = sys::text_input_alloc();
+            let view = sys::text_input_get_view(data);
+            let view = View::from_raw(view);
+            let data = NonNull::new_unchecked(data);
+            Self {
+                data,
+                view,
+                // room for the nul byte
+                buffer: vec![0; max_len + 1].into_boxed_slice(),
+                context: None,
+            }
+        }
+    }
+
+    pub fn set_header<'s>(&'s mut self, header: &'s CStr) {
+        unsafe {
+            sys::text_input_set_header_text(self.data.as_ptr(), header.as_ptr());
+        }
+    }
+
+    /// Sets the callback for when the text was entered, the text starts out
+    /// empty.
+    pub fn set_result_callback<C>(&mut self, context: Arc<C::Context>)
+    where
+        C: TextInputResult,
+    {
+        self.buffer.fill(0);
+        let context = Box::new(ResultContext {
+            buffer: self.buffer.as_ptr().cast(),
+            context,
+        });
+        unsafe {
+            sys::text_input_set_result_callback(
+                self.data.as_ptr(),
+                Some(C::__result),
+                (&*context as *const ResultContext<C::Context>)
+                    .cast_mut()
+                    .cast(),
+                self.buffer.as_mut_ptr().cast(),
+                self.buffer.len(),
+                true,
+            );
+        }
+        self.context = Some(context);
+    }
+
+    pub fn as_view(&self) -> &View {
+        &self.view
+    }
+
+    pub fn as_mut_view(&mut self) -> &mut View {
+        &mut self.view
+    }
+}
+
+pub trait TextInputResult {
+    type Context: Any;
+
+    fn result(context: &Self::Context, text: &CStr);
+
+    #[doc(hidden)]
+    unsafe extern "C" fn __result(context: *mut c_void) {
+        let context: *const ResultContext<Self::Context> = context.cast_const().cast();
+        let context = context.as_ref_unchecked();
+        let text = CStr::from_ptr(context.buffer);
+        Self::result(&context.context, text);
+    }
+}
+
+impl Drop for TextInput {
+    fn drop(&mut self) {
+        unsafe {
+            sys::text_input_free(self.data.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::sync::Arc;
+use core::{
+    any::Any,
+    ffi::{c_uchar, c_void},
+    ptr::NonNull,
+};
+use flipperzero::furi::time::Duration;
+use flipperzero_sys as sys;
+
+pub struct Timer {
+    pub(super) data: NonNull<sys::FuriTimer>,
+    // the timer only has a pointer into this, keep it alive as long as the timer
+    _context: Arc<dyn Any>,
+}
+
+unsafe impl Send for Timer {}
+unsafe impl Sync for Timer {}
+
+#[allow(unused)] // for completeness we have all variants
+#[repr(u8)]
+pub enum TimerType {
+    Once = sys::FuriTimerType_FuriTimerTypeOnce,
+    Periodic = sys::FuriTimerType_FuriTimerTypePeriodic,
+}
+
+impl Timer {
+    pub fn new<C: TimerCallback>(kind: TimerType, context: Arc<C::Context>) -> Self {
+        unsafe {
+            let context_ptr = Arc::as_ptr(&context).cast::<c_void>().cast_mut();
+            let data = sys::furi_timer_alloc(Some(C::__callback), kind as c_uchar, context_ptr);
+            Self {
+                data: NonNull::new_unchecked(data),
+                _context: context,
+            }
+        }
+    }
+
+    /// Starts the timer or restarts it if it is already running.
+    pub fn start(&self, interval: Duration) {
+        unsafe {
+            sys::furi_timer_start(self.data.as_ptr(), interval.as_ticks());
+        }
+    }
+}
+
+/// Called by the timer service thread, keep the work in here short.
+pub trait TimerCallback {
+    type Context: Any + Send + Sync;
+
+    fn callback(context: &Self::Context);
+
+    #[doc(hidden)]
+    unsafe extern "C" fn __callback(context: *mut c_void) {
+        let context: *const Self::Context = context.cast_const().cast();
+        let context: &Self::Context = context.as_ref_unchecked();
+        Self::callback(context);
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe {
+            // stopping waits for a running callback, so the context is unused afterwards
+            sys::furi_timer_stop(self.data.as_ptr());
+            sys::furi_timer_free(self.data.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::{
+    any::Any,
+    ffi::{c_void, CStr},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr::NonNull,
+};
+use flipperzero_sys as sys;
+
+use super::View;
+
+pub struct VariableItemList {
+    pub(super) data: NonNull<sys::VariableItemList>,
+    // this view is purely a reference to the list here, so don't try to drop it
+    view: ManuallyDrop<View>,
+    // the items only have pointers into these
+    contexts: Vec<Arc<dyn Any>>,
+}
+
+/// An item owned by a [`VariableItemList`].
+pub struct VariableItem<'l> {
+    data: NonNull<sys::VariableItem>,
+    _list: PhantomData<&'l VariableItemList>,
+}
+
+impl VariableItemList {
+    pub fn new() -> Self {
+        unsafe {
+            let data = sys::variable_item_list_alloc();
+            let view = sys::variable_item_list_get_view(data);
+            let view = View::from_raw(view);
+            let data = NonNull::new_unchecked(data);
+            Self {
+                data,
+                view,
+                contexts: Vec::new(),
+            }
+        }
+    }
+
+    /// Adds an item that can be switched between `values_count` values with
+    /// left and right, `I` is called after every switch.
+    pub fn add_item<'l, I>(
+        &'l mut self,
+        label: &'l CStr,
+        values_count: u8,
+        context: Arc<I::Context>,
+    ) -> VariableItem<'l>
+    where
+        I: VariableItemChange,
+    {
+        unsafe {
+            let item = sys::variable_item_list_add(
+                self.data.as_ptr(),
+                label.as_ptr(),
+                values_count,
+                Some(I::__change),
+                Arc::as_ptr(&context).cast::<c_void>().cast_mut(),
+            );
+            self.contexts.push(context);
+            VariableItem {
+                data: NonNull::new_unchecked(item),
+                _list: PhantomData,
+            }
+        }
+    }
+
+    pub fn as_view(&self) -> &View {
+        &self.view
+    }
+
+    pub fn as_mut_view(&mut self) -> &mut View {
+        &mut self.view
+    }
+}
+
+impl VariableItem<'_> {
+    pub fn current_value_index(&self) -> u8 {
+        unsafe { sys::variable_item_get_current_value_index(self.data.as_ptr()) }
+    }
+
+    pub fn set_current_value_index(&mut self, index: u8) {
+        unsafe {
+            sys::variable_item_set_current_value_index(self.data.as_ptr(), index);
+        }
+    }
+
+    /// Sets the text shown for the current value, the text is copied.
+    pub fn set_current_value_text(&mut self, text: &CStr) {
+        unsafe {
+            sys::variable_item_set_current_value_text(self.data.as_ptr(), text.as_ptr());
+        }
+    }
+}
+
+pub trait VariableItemChange {
+    type Context: Any;
+
+    fn change(context: &Self::Context, item: &mut VariableItem);
+
+    #[doc(hidden)]
+    unsafe extern "C" fn __change(item: *mut sys::VariableItem) {
+        let context = sys::variable_item_get_context(item);
+        let context: *const Self::Context = context.cast_const().cast();
+        let context: &Self::Context = context.as_ref_unchecked();
+        let mut item = VariableItem {
+            data: NonNull::new_unchecked(item),
+            _list: PhantomData,
+        };
+        Self::change(context, &mut item);
+    }
+}
+
+impl Drop for VariableItemList {
+    fn drop(&mut self) {
+        unsafe {
+            sys::variable_item_list_free(self.data.as_ptr());
+        }
+    }
+}
@@ -1,122 +1,183 @@
 use alloc::sync::Arc;
 use core::{
-    any::Any,
     ffi::c_void,
+    marker::PhantomData,
     mem::{ManuallyDrop, MaybeUninit},
-    ptr::NonNull,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicBool, Ordering},
 };
 use flipperzero_sys as sys;
 
 use super::Canvas;
 
-pub struct View {
+/// A view with a context of type `C` for its callbacks and a model of type
+/// `M` to draw.
+///
+/// Zero-sized contexts and models, like the default `()`, aren't allocated.
+pub struct View<C = (), M = ()> {
     pub(super) data: NonNull<sys::View>,
-    pub(super) context: Option<Arc<dyn Any>>,
-    pub(super) has_model: bool,
+    // the callbacks only get a pointer to this
+    _context: Option<Arc<C>>,
+    _model: PhantomData<M>,
+    // the model lock is recursive, so it doesn't keep a thread from
+    // borrowing the model twice
+    borrowed: AtomicBool,
 }
 
-unsafe impl Send for View {}
-unsafe impl Sync for View {}
+// the callbacks share the context, the model moves between the threads
+// taking turns on its lock
+unsafe impl<C: Send + Sync, M: Send> Send for View<C, M> {}
+unsafe impl<C: Send + Sync, M: Send> Sync for View<C, M> {}
 
-impl View {
+impl<M: Default> View<(), M> {
     pub fn new() -> Self {
-        unsafe {
-            Self {
-                data: NonNull::new_unchecked(sys::view_alloc()),
-                context: None,
-                has_model: false,
-            }
-        }
+        unsafe { Self::alloc(None) }
     }
+}
 
-    pub fn create_model<M: Any + Default + Sized>(&mut self) -> ModelGuard<M> {
-        self.has_model = true;
+impl View {
+    /// Wraps the view of a firmware module, which keeps its own context and
+    /// model in it.
+    ///
+    /// # Safety
+    ///
+    /// `data` has to be a valid view, which must not be dropped as a `View`.
+    pub(super) unsafe fn from_raw(data: *mut sys::View) -> ManuallyDrop<Self> {
+        ManuallyDrop::new(Self {
+            data: NonNull::new_unchecked(data),
+            _context: None,
+            _model: PhantomData,
+            borrowed: AtomicBool::new(false),
+        })
+    }
+}
 
-        let model_type = sys::ViewModelType_ViewModelTypeLocking;
-        unsafe {
-            sys::view_allocate_model(self.data.as_ptr(), model_type, size_of::<M>());
-            let model = sys::view_get_model(self.data.as_ptr());
-            let model: *mut MaybeUninit<M> = model.cast();
-            let model: &mut MaybeUninit<M> = model.as_mut_unchecked();
-            let model = model.write(M::default());
-            ModelGuard {
-                view: &self.data,
-                model,
-            }
-        }
+impl<C, M: Default> View<C, M> {
+    pub fn with_context(context: Arc<C>) -> Self {
+        unsafe { Self::alloc(Some(context)) }
     }
 
-    pub fn get_model<M: Any>(&self) -> Option<ModelGuard<M>> {
-        if !self.has_model {
-            return None;
+    unsafe fn alloc(context: Option<Arc<C>>) -> Self {
+        let data = sys::view_alloc();
+        if let Some(context) = context.as_ref() {
+            sys::view_set_context(data, Arc::as_ptr(context).cast::<c_void>().cast_mut());
+        }
+        if size_of::<M>() != 0 {
+            let model_type = sys::ViewModelType_ViewModelTypeLocking;
+            sys::view_allocate_model(data, model_type, size_of::<M>());
+            let model = sys::view_get_model(data);
+            let model: *mut MaybeUninit<M> = model.cast();
+            model.as_mut_unchecked().write(M::default());
+            sys::view_commit_model(data, false);
+        }
+        Self {
+            data: NonNull::new_unchecked(data),
+            _context: context,
+            _model: PhantomData,
+            borrowed: AtomicBool::new(false),
         }
+    }
+}
 
+impl<C, M> View<C, M> {
+    /// Locks the model, the view is redrawn once the guard is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this thread already holds a guard of this view.
+    pub fn model(&self) -> ModelGuard<'_, M> {
         unsafe {
             let model = sys::view_get_model(self.data.as_ptr());
-            let model: *mut dyn Any = model.cast::<M>();
-            let model: &mut dyn Any = model.as_mut_unchecked();
-            let model = model.downcast_mut()?;
-            Some(ModelGuard {
+            // only this thread can have set it while we hold the lock, there's
+            // no lock and nothing to borrow for zero-sized models
+            if size_of::<M>() != 0 && self.borrowed.swap(true, Ordering::Acquire) {
+                sys::view_commit_model(self.data.as_ptr(), false);
+                panic!("model of the view is already borrowed");
+            }
+            ModelGuard {
                 view: &self.data,
-                model,
-            })
+                borrowed: &self.borrowed,
+                model: typed_ptr::<M>(model).as_mut(),
+            }
         }
     }
 
-    pub fn set_context(&mut self, context: Arc<dyn Any>) {
+    pub fn set_previous_callback<N: ViewNavigationCallback<C>>(&mut self) {
         unsafe {
-            sys::view_set_context(
-                self.data.as_ptr(),
-                Arc::as_ptr(&context).cast::<c_void>().cast_mut(),
-            );
+            sys::view_set_previous_callback(self.data.as_ptr(), Some(N::__callback));
         }
-
-        self.context = Some(context);
     }
 
-    pub fn set_previous_callback<C: ViewNavigationCallback>(&mut self) {
+    pub fn set_enter_callback<E: ViewEnterCallback<Context = C>>(&mut self) {
         unsafe {
-            sys::view_set_previous_callback(self.data.as_ptr(), Some(C::__callback));
+            sys::view_set_enter_callback(self.data.as_ptr(), Some(E::__callback));
         }
     }
 
-    pub fn set_draw_callback<C: ViewDrawCallback>(&mut self) {
+    pub fn set_draw_callback<D: ViewDrawCallback<Model = M>>(&mut self) {
         unsafe {
-            sys::view_set_draw_callback(self.data.as_ptr(), Some(C::__callback));
+            sys::view_set_draw_callback(self.data.as_ptr(), Some(D::__callback));
         }
     }
 
-    pub fn set_input_callback<C: ViewInputCallback>(&mut self) {
+    pub fn set_input_callback<I: ViewInputCallback<Context = C>>(&mut self) {
         unsafe {
-            sys::view_set_input_callback(self.data.as_ptr(), Some(C::__callback));
+            sys::view_set_input_callback(self.data.as_ptr(), Some(I::__callback));
         }
     }
 }
 
+/// Turns a context or model pointer from the firmware back into its type.
+///
+/// Zero-sized types are never allocated, the firmware has a null pointer or
+/// one to its own data for them.
+///
+/// # Safety
+///
+/// Unless `T` is zero-sized, `ptr` has to point to a valid `T`.
+unsafe fn typed_ptr<T>(ptr: *mut c_void) -> NonNull<T> {
+    match size_of::<T>() {
+        0 => NonNull::dangling(),
+        _ => NonNull::new_unchecked(ptr.cast()),
+    }
+}
+
 pub struct ModelGuard<'m, M> {
     pub(super) view: &'m NonNull<sys::View>,
+    borrowed: &'m AtomicBool,
     pub model: &'m mut M,
 }
 
 impl<M> Drop for ModelGuard<'_, M> {
     fn drop(&mut self) {
+        // before unlocking, the next one to lock may be another thread
+        self.borrowed.store(false, Ordering::Release);
         unsafe {
             sys::view_commit_model(self.view.as_ptr(), true);
         }
     }
 }
 
-pub trait ViewNavigationCallback {
-    type Context: Any;
-
-    fn callback(context: Option<&Self::Context>) -> u32;
+/// Decides which view to go to on back, generic over the context so that
+/// views going to a fixed view share one implementation.
+pub trait ViewNavigationCallback<C> {
+    fn callback(context: &C) -> u32;
 
     #[doc(hidden)]
     unsafe extern "C" fn __callback(context: *mut c_void) -> u32 {
-        let context: *const dyn Any = context.cast_const().cast::<Self::Context>();
-        let context: Option<&dyn Any> = context.as_ref();
-        let context: Option<&Self::Context> = context.map(|any| any.downcast_ref()).flatten();
-        Self::callback(context)
+        Self::callback(typed_ptr::<C>(context).as_ref())
+    }
+}
+
+/// Called whenever the view dispatcher switches to the view.
+pub trait ViewEnterCallback {
+    type Context;
+
+    fn callback(context: &Self::Context);
+
+    #[doc(hidden)]
+    unsafe extern "C" fn __callback(context: *mut c_void) {
+        Self::callback(typed_ptr::<Self::Context>(context).as_ref())
     }
 }
 
@@ -130,36 +191,82 @@ pub enum InputKey {
     Ok = sys::InputKey_InputKeyOk,
     Back = sys::InputKey_InputKeyBack,
 }
+
+impl InputKey {
+    fn from_raw(key: sys::InputKey) -> Option<Self> {
+        Some(match key {
+            sys::InputKey_InputKeyUp => InputKey::Up,
+            sys::InputKey_InputKeyDown => InputKey::Down,
+            sys::InputKey_InputKeyRight => InputKey::Right,
+            sys::InputKey_InputKeyLeft => InputKey::Left,
+            sys::InputKey_InputKeyOk => InputKey::Ok,
+            sys::InputKey_InputKeyBack => InputKey::Back,
+            _ => return None,
+        })
+    }
+}
+
+/// What happened to a key.
+///
+/// A key sends `Press` and `Release`, in between `Short` if it was released
+/// quickly, or `Long` once it was held and then `Repeat` while it is held.
+#[allow(unused)] // for completeness we have all variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InputType {
+    Press = sys::InputType_InputTypePress,
+    Release = sys::InputType_InputTypeRelease,
+    Short = sys::InputType_InputTypeShort,
+    Long = sys::InputType_InputTypeLong,
+    Repeat = sys::InputType_InputTypeRepeat,
+}
+
+impl InputType {
+    fn from_raw(kind: sys::InputType) -> Option<Self> {
+        Some(match kind {
+            sys::InputType_InputTypePress => InputType::Press,
+            sys::InputType_InputTypeRelease => InputType::Release,
+            sys::InputType_InputTypeShort => InputType::Short,
+            sys::InputType_InputTypeLong => InputType::Long,
+            sys::InputType_InputTypeRepeat => InputType::Repeat,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    pub key: InputKey,
+    pub kind: InputType,
+}
+
 pub trait ViewInputCallback {
-    type Context: Any;
+    type Context;
 
-    fn callback(input_key: InputKey, context: Option<&Self::Context>) -> bool;
+    /// Returns whether the event was handled, unhandled `Back` events
+    /// navigate to the previous view.
+    fn callback(event: InputEvent, context: &Self::Context) -> bool;
 
     #[doc(hidden)]
     unsafe extern "C" fn __callback(event: *mut sys::InputEvent, context: *mut c_void) -> bool {
-        let context: *const dyn Any = context.cast_const().cast::<Self::Context>();
-        let context: Option<&dyn Any> = context.as_ref();
-        let context: Option<&Self::Context> = context.map(|any| any.downcast_ref()).flatten();
+        let context = typed_ptr::<Self::Context>(context).as_ref();
 
         let event: &mut sys::InputEvent = event.as_mut_unchecked();
-        let input_key = match event.key {
-            0 => InputKey::Up,
-            1 => InputKey::Down,
-            2 => InputKey::Right,
-            3 => InputKey::Left,
-            4 => InputKey::Ok,
-            5 => InputKey::Back,
-            _ => unreachable!(),
+        let (Some(key), Some(kind)) = (
+            InputKey::from_raw(event.key),
+            InputType::from_raw(event.type_),
+        ) else {
+            return false;
         };
 
-        Self::callback(input_key, context)
+        Self::callback(InputEvent { key, kind }, context)
     }
 }
 
 pub trait ViewDrawCallback {
-    type Model: Any;
+    type Model;
 
-    fn callback(canvas: &mut Canvas, model: Option<&Self::Model>);
+    fn callback(canvas: &mut Canvas, model: &Self::Model);
 
     #[doc(hidden)]
     unsafe extern "C" fn __callback(canvas: *mut sys::Canvas, model: *mut c_void) {
@@ -168,18 +275,17 @@ pub trait ViewDrawCallback {
         };
         let mut canvas = ManuallyDrop::new(canvas);
 
-        let model: *const dyn Any = model.cast_const().cast::<Self::Model>();
-        let model: Option<&dyn Any> = model.as_ref();
-        let model: Option<&Self::Model> = model.map(|any| any.downcast_ref()).flatten();
-
-        Self::callback(&mut canvas, model);
+        Self::callback(&mut canvas, typed_ptr::<Self::Model>(model).as_ref());
     }
 }
 
-impl Drop for View {
+impl<C, M> Drop for View<C, M> {
     fn drop(&mut self) {
         unsafe {
-            if self.has_model {
+            if size_of::<M>() != 0 {
+                let model = sys::view_get_model(self.data.as_ptr());
+                ptr::drop_in_place(model.cast::<M>());
+                sys::view_commit_model(self.data.as_ptr(), false);
                 sys::view_free_model(self.data.as_ptr());
             }
 
@@ -190,10 +296,8 @@ impl Drop for View {
 
 pub struct ViewNone;
 
-impl ViewNavigationCallback for ViewNone {
-    type Context = ();
-
-    fn callback(_: Option<&Self::Context>) -> u32 {
+impl<C> ViewNavigationCallback<C> for ViewNone {
+    fn callback(_: &C) -> u32 {
         const VIEW_NONE: u32 = 0xFFFFFFFF;
         VIEW_NONE
     }
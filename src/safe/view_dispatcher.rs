@@ -1,20 +1,33 @@
-use super::{gui::Gui, submenu::Submenu, View, Widget};
+use super::{gui::Gui, submenu::Submenu, DialogEx, TextInput, VariableItemList, View, Widget};
 use alloc::{
     sync::{Arc, Weak},
     vec::Vec,
 };
-use core::{ffi::c_uchar, ptr::NonNull};
+use core::{
+    any::Any,
+    ffi::{c_uchar, c_void},
+    ptr::NonNull,
+};
 use flipperzero::furi::sync::Mutex;
 use flipperzero_sys as sys;
 
+/// Custom events with this bit set ask the dispatcher to switch to the view
+/// in the other bits.
+const SWITCH_EVENT: u32 = 1 << 31;
+
 pub struct ViewDispatcher {
     pub(super) data: NonNull<sys::ViewDispatcher>,
+    // shared with the view switchers, cleared when the dispatcher is dropped
+    shared: Arc<Mutex<Shared>>,
 
     pub(super) gui: Gui,
 
-    pub(super) views: Vec<(u32, Arc<View>)>,
+    pub(super) views: Vec<(u32, Arc<dyn Any>)>,
     pub(super) submenus: Vec<(u32, Submenu)>,
     pub(super) widgets: Vec<(u32, Arc<Mutex<Widget>>)>,
+    pub(super) variable_item_lists: Vec<(u32, VariableItemList)>,
+    pub(super) text_inputs: Vec<(u32, TextInput)>,
+    pub(super) dialogs: Vec<(u32, Arc<Mutex<DialogEx>>)>,
 }
 
 #[allow(unused)]
@@ -26,16 +39,28 @@ pub enum ViewDispatcherType {
 }
 
 impl ViewDispatcher {
+    /// Creates a dispatcher to be run on the current thread.
     pub fn new(gui: Gui, kind: ViewDispatcherType) -> Self {
         unsafe {
+            let data = NonNull::new_unchecked(sys::view_dispatcher_alloc());
+            sys::view_dispatcher_set_event_callback_context(data.as_ptr(), data.as_ptr().cast());
+            sys::view_dispatcher_set_custom_event_callback(data.as_ptr(), Some(custom_event));
+
             let view_dispatcher = Self {
-                data: NonNull::new_unchecked(sys::view_dispatcher_alloc()),
+                data,
+                shared: Arc::new(Mutex::new(Shared {
+                    data: Some(data),
+                    thread: sys::furi_thread_get_current_id(),
+                })),
 
                 gui,
 
                 views: Vec::new(),
                 submenus: Vec::new(),
                 widgets: Vec::new(),
+                variable_item_lists: Vec::new(),
+                text_inputs: Vec::new(),
+                dialogs: Vec::new(),
             };
 
             sys::view_dispatcher_attach_to_gui(
@@ -50,17 +75,21 @@ impl ViewDispatcher {
 
     pub fn view_switcher(&self) -> ViewSwitcher {
         ViewSwitcher {
-            view_dispatcher_ptr: self.data.as_ptr(),
+            shared: self.shared.clone(),
         }
     }
 
-    pub fn add_view(&mut self, view: View, view_id: u32) -> Weak<View> {
+    pub fn add_view<C: 'static, M: 'static>(
+        &mut self,
+        view: View<C, M>,
+        view_id: u32,
+    ) -> Weak<View<C, M>> {
         unsafe {
             sys::view_dispatcher_add_view(self.data.as_ptr(), view_id, view.data.as_ptr());
         }
         let view = Arc::new(view);
         let weak = Arc::downgrade(&view);
-        self.views.push((view_id, view));
+        self.views.push((view_id, view as Arc<dyn Any>));
         weak
     }
 
@@ -89,27 +118,60 @@ impl ViewDispatcher {
         weak
     }
 
-    pub fn switch_to_view(&mut self, view_id: u32) {
+    pub fn add_variable_item_list(&mut self, list: VariableItemList, view_id: u32) {
         unsafe {
-            sys::view_dispatcher_switch_to_view(self.data.as_ptr(), view_id);
+            sys::view_dispatcher_add_view(
+                self.data.as_ptr(),
+                view_id,
+                list.as_view().data.as_ptr(),
+            );
         }
+        self.variable_item_lists.push((view_id, list));
     }
 
-    pub fn run(&mut self) {
+    pub fn add_text_input(&mut self, text_input: TextInput, view_id: u32) {
         unsafe {
-            sys::view_dispatcher_run(self.data.as_ptr());
+            sys::view_dispatcher_add_view(
+                self.data.as_ptr(),
+                view_id,
+                text_input.as_view().data.as_ptr(),
+            );
         }
+        self.text_inputs.push((view_id, text_input));
     }
 
-    pub fn stop(&self) {
+    pub fn add_dialog_ex_mutex(&mut self, dialog: DialogEx, view_id: u32) -> Weak<Mutex<DialogEx>> {
         unsafe {
-            sys::view_dispatcher_stop(self.data.as_ptr());
+            sys::view_dispatcher_add_view(
+                self.data.as_ptr(),
+                view_id,
+                dialog.as_view().data.as_ptr(),
+            );
+        }
+        let dialog = Arc::new(Mutex::new(dialog));
+        let weak = Arc::downgrade(&dialog);
+        self.dialogs.push((view_id, dialog));
+        weak
+    }
+
+    pub fn switch_to_view(&mut self, view_id: u32) {
+        unsafe {
+            sys::view_dispatcher_switch_to_view(self.data.as_ptr(), view_id);
+        }
+    }
+
+    pub fn run(&mut self) {
+        unsafe {
+            sys::view_dispatcher_run(self.data.as_ptr());
         }
     }
 }
 
 impl Drop for ViewDispatcher {
     fn drop(&mut self) {
+        // waits for switches in progress on other threads
+        self.shared.lock().data = None;
+
         unsafe {
             for view_id in self
                 .views
@@ -117,6 +179,9 @@ impl Drop for ViewDispatcher {
                 .map(|(view_id, _)| view_id)
                 .chain(self.submenus.iter().map(|(view_id, _)| view_id))
                 .chain(self.widgets.iter().map(|(view_id, _)| view_id))
+                .chain(self.variable_item_lists.iter().map(|(view_id, _)| view_id))
+                .chain(self.text_inputs.iter().map(|(view_id, _)| view_id))
+                .chain(self.dialogs.iter().map(|(view_id, _)| view_id))
                 .copied()
             {
                 sys::view_dispatcher_remove_view(self.data.as_ptr(), view_id);
@@ -127,15 +192,57 @@ impl Drop for ViewDispatcher {
     }
 }
 
-// not safest implementation but eh
+/// Dispatcher state the switchers share.
+struct Shared {
+    /// `None` once the dispatcher is dropped.
+    data: Option<NonNull<sys::ViewDispatcher>>,
+    /// The thread running the dispatcher.
+    thread: sys::FuriThreadId,
+}
+
+// only used while locked
+unsafe impl Send for Shared {}
+
+unsafe extern "C" fn custom_event(context: *mut c_void, event: u32) -> bool {
+    if event & SWITCH_EVENT == 0 {
+        return false;
+    }
+
+    let view_dispatcher: *mut sys::ViewDispatcher = context.cast();
+    sys::view_dispatcher_switch_to_view(view_dispatcher, event & !SWITCH_EVENT);
+    true
+}
+
+/// Handle to switch views of a [`ViewDispatcher`] from callbacks and other
+/// threads.
+///
+/// It does nothing once the dispatcher is dropped.
+#[derive(Clone)]
 pub struct ViewSwitcher {
-    pub(super) view_dispatcher_ptr: *mut sys::ViewDispatcher,
+    shared: Arc<Mutex<Shared>>,
 }
 
 impl ViewSwitcher {
+    /// Switches right away on the dispatcher's thread, e.g. in view
+    /// callbacks, other threads queue the switch for the dispatcher.
     pub fn switch_to_view(&self, view_id: u32) {
+        let shared = self.shared.lock();
+        let Some(data) = shared.data else { return };
+        unsafe {
+            match sys::furi_thread_get_current_id() == shared.thread {
+                true => sys::view_dispatcher_switch_to_view(data.as_ptr(), view_id),
+                false => {
+                    sys::view_dispatcher_send_custom_event(data.as_ptr(), SWITCH_EVENT | view_id)
+                }
+            }
+        }
+    }
+
+    pub fn stop(&self) {
+        let shared = self.shared.lock();
+        let Some(data) = shared.data else { return };
         unsafe {
-            sys::view_dispatcher_switch_to_view(self.view_dispatcher_ptr, view_id);
+            sys::view_dispatcher_stop(data.as_ptr());
         }
     }
 }
@@ -17,12 +17,7 @@ impl Widget {
         unsafe {
             let data = sys::widget_alloc();
             let view = sys::widget_get_view(data);
-            let view = View {
-                data: NonNull::new_unchecked(view),
-                context: None,
-                has_model: false,
-            };
-            let view = ManuallyDrop::new(view);
+            let view = View::from_raw(view);
             let data = NonNull::new_unchecked(data);
             Self { data, view }
         }
@@ -0,0 +1,170 @@
+//! Command scripts on the SD card.
+//!
+//! Scripts are text files in [`SCRIPT_DIR`], see [`ScriptStep`] for their
+//! format.
+//! Every run is appended to a transcript next to the script, with the same
+//! name but [`TRANSCRIPT_EXTENSION`].
+
+use core::{ffi::CStr, fmt::Write};
+
+use alloc::{ffi::CString, format, string::String, vec::Vec};
+use flipperzero::furi::{string::FuriString, time::Instant};
+use rg15_protocol::{ScriptError, ScriptStep};
+
+use crate::{run_view, safe::*};
+
+pub const SCRIPT_DIR: &CStr = c"/ext/apps_data/rg15/scripts";
+pub const SCRIPT_EXTENSION: &CStr = c".txt";
+const TRANSCRIPT_EXTENSION: &str = ".log";
+
+pub struct Script {
+    pub name: String,
+    path: String,
+    /// Lines with a step and their line numbers, starting at 1.
+    pub lines: Vec<(u32, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLoadError {
+    Unreadable,
+    InvalidLine(u32, ScriptError),
+}
+
+impl Script {
+    /// Reads the script at `path` and checks every line, so that a typo
+    /// doesn't stop it halfway.
+    pub fn load(path: &CStr) -> Result<Self, ScriptLoadError> {
+        let file = File::open(path, FileAccess::Read, FileOpen::Existing)
+            .ok_or(ScriptLoadError::Unreadable)?;
+
+        let path = path.to_str().map_err(|_| ScriptLoadError::Unreadable)?;
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let extension = SCRIPT_EXTENSION.to_str().expect("valid utf-8");
+        let name = file_name.strip_suffix(extension).unwrap_or(file_name);
+
+        let mut lines = Vec::new();
+        let mut file_lines = file.lines();
+        let mut line = String::new();
+        let mut number = 0;
+        while file_lines.next_line(&mut line) {
+            number += 1;
+            match ScriptStep::parse(&line) {
+                Ok(None) => (),
+                Ok(Some(_)) => lines.push((number, String::from(line.trim()))),
+                Err(error) => return Err(ScriptLoadError::InvalidLine(number, error)),
+            }
+        }
+
+        Ok(Self {
+            name: String::from(name),
+            path: String::from(path),
+            lines,
+        })
+    }
+
+    /// Iterates over the steps and their line numbers.
+    pub fn steps(&self) -> impl Iterator<Item = (u32, ScriptStep<'_>)> {
+        self.lines.iter().filter_map(|(number, line)| {
+            let step = ScriptStep::parse(line).ok().flatten()?;
+            Some((*number, step))
+        })
+    }
+}
+
+/// Record of a script run, each entry with the milliseconds since the start.
+pub struct Transcript {
+    // `None` without SD card, the run is still shown on screen
+    file: Option<File>,
+    start: Instant,
+}
+
+impl Transcript {
+    pub fn open(script: &Script) -> Self {
+        let base = script
+            .path
+            .strip_suffix(SCRIPT_EXTENSION.to_str().expect("valid utf-8"));
+        let path = format!("{}{TRANSCRIPT_EXTENSION}", base.unwrap_or(&script.path));
+        let path = CString::new(path).expect("no nul bytes in path");
+        let mut file = File::open(&path, FileAccess::Write, FileOpen::Append);
+        if let Some(file) = file.as_mut() {
+            let header = format!("# {} {}\n", script.name, DateTime::now());
+            file.write(header.as_bytes());
+        }
+
+        Self {
+            file,
+            start: Instant::now(),
+        }
+    }
+
+    /// Adds an entry, `marker` tells what it is: `>` sent, `<` received, `#`
+    /// a note and `!` a failure.
+    ///
+    /// Returns the entry without the time for display.
+    pub fn write(&mut self, marker: char, text: &str) -> String {
+        let entry = format!("{marker} {text}");
+        if let Some(file) = self.file.as_mut() {
+            let millis = self.start.elapsed().as_millis();
+            file.write(format!("{millis:>7} {entry}\n").as_bytes());
+        }
+        entry
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptState {
+    #[default]
+    Running,
+    Passed,
+    /// Failed at the step in this line.
+    Failed(u32),
+    /// Stopped with Back before the end.
+    Cancelled,
+}
+
+/// Progress of a script run for the [`ScriptView`].
+#[derive(Default)]
+pub struct ScriptRun {
+    pub name: CString,
+    pub step: usize,
+    pub total: usize,
+    /// The latest transcript entry.
+    pub last: CString,
+    pub state: ScriptState,
+}
+
+impl ScriptRun {
+    pub fn new(script: &Script) -> Self {
+        Self {
+            name: CString::new(script.name.as_str()).unwrap_or_default(),
+            total: script.lines.len(),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_last(&mut self, entry: &str) {
+        self.last = CString::new(entry).unwrap_or_default();
+    }
+}
+
+pub struct ScriptView;
+
+impl ViewDrawCallback for ScriptView {
+    type Model = ScriptRun;
+
+    fn callback(canvas: &mut Canvas, run: &Self::Model) {
+        let mut status = FuriString::new();
+        match run.state {
+            ScriptState::Running => write!(status, "Step {}/{}", run.step + 1, run.total),
+            ScriptState::Passed => write!(status, "Passed"),
+            ScriptState::Failed(line) => write!(status, "Failed in line {line}"),
+            ScriptState::Cancelled => write!(status, "Cancelled"),
+        }
+        .expect("infallible");
+        let running = run.state == ScriptState::Running && run.total > 0;
+        let progress = running.then_some(run.step as f32 / run.total as f32);
+        run_view::draw_header(canvas, &run.name, status.as_c_str(), progress);
+
+        canvas.draw_str(run_view::PADDING, 40, &run.last);
+    }
+}
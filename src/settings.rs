@@ -0,0 +1,302 @@
+//! App settings on the SD card.
+//!
+//! The file has one `key=value` pair per line, e.g. `baud_rate=19200`.
+//! Unknown keys and invalid values are ignored and keep their default, so a
+//! broken file never prevents the app from starting.
+
+use core::{ffi::CStr, fmt::Write};
+
+use alloc::string::String;
+use rg15_protocol::{BaudRate, Measurement, UnitSystem};
+
+use crate::{logger::DATA_DIR, poll::POLL_INTERVALS, safe::*};
+
+const SETTINGS_PATH: &CStr = c"/ext/apps_data/rg15/settings.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Port {
+    Usart,
+    Lpuart,
+}
+
+impl Port {
+    pub fn list() -> [Self; 2] {
+        [Port::Usart, Port::Lpuart]
+    }
+
+    pub fn name(self) -> &'static CStr {
+        match self {
+            Port::Usart => c"USART",
+            Port::Lpuart => c"LPUART",
+        }
+    }
+
+    /// Name with the GPIO pin numbers the sensor has to be connected to.
+    pub fn name_with_pins(self) -> &'static CStr {
+        match self {
+            Port::Usart => c"USART (13 TX, 14 RX)",
+            Port::Lpuart => c"LPUART (15 TX, 16 RX)",
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Port::Usart => Port::Lpuart,
+            Port::Lpuart => Port::Usart,
+        }
+    }
+
+    pub fn serial_id(self) -> SerialId {
+        match self {
+            Port::Usart => SerialId::Usart,
+            Port::Lpuart => SerialId::Lpuart,
+        }
+    }
+}
+
+/// Units readings are shown in, independent of what the sensor sends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayUnits {
+    /// As reported by the sensor.
+    #[default]
+    Sensor,
+    Metric,
+    Imperial,
+}
+
+impl DisplayUnits {
+    pub fn apply(self, measurement: Measurement) -> Measurement {
+        match self {
+            DisplayUnits::Sensor => measurement,
+            DisplayUnits::Metric => measurement.to_system(UnitSystem::Metric),
+            DisplayUnits::Imperial => measurement.to_system(UnitSystem::Imperial),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// Rate the sensor last answered at, `None` asks at startup.
+    pub baud_rate: Option<BaudRate>,
+    /// `None` asks at startup.
+    pub port: Option<Port>,
+    /// Automatic poll interval in seconds, 0 is off.
+    pub poll_interval: u32,
+    pub logging: bool,
+    pub units: DisplayUnits,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            baud_rate: None,
+            port: None,
+            poll_interval: 0,
+            logging: true,
+            units: DisplayUnits::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings file, the defaults if there is none.
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        let Some(file) = File::open(SETTINGS_PATH, FileAccess::Read, FileOpen::Existing) else {
+            return settings;
+        };
+
+        let mut lines = file.lines();
+        let mut line = String::new();
+        while lines.next_line(&mut line) {
+            if let Some((key, value)) = line.split_once('=') {
+                settings.set(key.trim(), value.trim());
+            }
+        }
+
+        settings
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match (key, value) {
+            ("baud_rate", "ask") => self.baud_rate = None,
+            ("baud_rate", rate) => {
+                let rate = rate.parse::<u16>();
+                let baud_rate = BaudRate::list()
+                    .into_iter()
+                    .find(|baud_rate| rate == Ok(baud_rate.rate()));
+                if baud_rate.is_some() {
+                    self.baud_rate = baud_rate;
+                }
+            }
+            ("port", "ask") => self.port = None,
+            ("port", "usart") => self.port = Some(Port::Usart),
+            ("port", "lpuart") => self.port = Some(Port::Lpuart),
+            // only the intervals the settings view can show
+            ("poll_interval", seconds) => {
+                let seconds = seconds.parse::<u32>();
+                let listed = POLL_INTERVALS
+                    .iter()
+                    .find(|(_, interval)| seconds == Ok(*interval));
+                if let Some((_, interval)) = listed {
+                    self.poll_interval = *interval;
+                }
+            }
+            ("logging", "on") => self.logging = true,
+            ("logging", "off") => self.logging = false,
+            ("units", "sensor") => self.units = DisplayUnits::Sensor,
+            ("units", "metric") => self.units = DisplayUnits::Metric,
+            ("units", "imperial") => self.units = DisplayUnits::Imperial,
+            _ => (),
+        }
+    }
+
+    /// Writes the settings file, returns whether that worked.
+    pub fn save(&self) -> bool {
+        let storage = Storage::open();
+        if !storage.mkdir(DATA_DIR) {
+            return false;
+        }
+        let Some(mut file) = File::open(SETTINGS_PATH, FileAccess::Write, FileOpen::CreateAlways)
+        else {
+            return false;
+        };
+
+        let mut text = String::new();
+        match self.baud_rate {
+            Some(baud_rate) => writeln!(text, "baud_rate={}", baud_rate.rate()),
+            None => writeln!(text, "baud_rate=ask"),
+        }
+        .expect("infallible");
+        let port = match self.port {
+            None => "ask",
+            Some(Port::Usart) => "usart",
+            Some(Port::Lpuart) => "lpuart",
+        };
+        let logging = match self.logging {
+            true => "on",
+            false => "off",
+        };
+        let units = match self.units {
+            DisplayUnits::Sensor => "sensor",
+            DisplayUnits::Metric => "metric",
+            DisplayUnits::Imperial => "imperial",
+        };
+        write!(
+            text,
+            "port={port}\npoll_interval={}\nlogging={logging}\nunits={units}\n",
+            self.poll_interval
+        )
+        .expect("infallible");
+
+        file.write(text.as_bytes()) == text.len()
+    }
+}
+
+/// A single entry of the settings view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    BaudRate,
+    Port,
+    PollInterval,
+    Logging,
+    Units,
+}
+
+impl Setting {
+    pub fn list() -> [Self; 5] {
+        [
+            Setting::BaudRate,
+            Setting::Port,
+            Setting::PollInterval,
+            Setting::Logging,
+            Setting::Units,
+        ]
+    }
+
+    pub fn label(self) -> &'static CStr {
+        match self {
+            Setting::BaudRate => c"Startup Baud",
+            Setting::Port => c"Serial Port",
+            Setting::PollInterval => c"Auto Poll",
+            Setting::Logging => c"CSV Logging",
+            Setting::Units => c"Units",
+        }
+    }
+
+    pub fn values_count(self) -> u8 {
+        match self {
+            Setting::BaudRate => BaudRate::list().len() as u8 + 1,
+            Setting::Port => Port::list().len() as u8 + 1,
+            Setting::PollInterval => POLL_INTERVALS.len() as u8,
+            Setting::Logging => 2,
+            Setting::Units => 3,
+        }
+    }
+
+    pub fn value_name(self, index: u8) -> &'static CStr {
+        let index = index as usize;
+        match self {
+            Setting::BaudRate => match index.checked_sub(1) {
+                None => c"Ask",
+                Some(index) => BaudRate::list()[index].rate_as_char(),
+            },
+            Setting::Port => match index.checked_sub(1) {
+                None => c"Ask",
+                Some(index) => Port::list()[index].name(),
+            },
+            Setting::PollInterval => POLL_INTERVALS[index].0,
+            Setting::Logging => [c"Off", c"On"][index],
+            Setting::Units => [c"Sensor", c"Metric", c"Imperial"][index],
+        }
+    }
+
+    /// Index of the value `settings` currently has for this entry.
+    pub fn value_index(self, settings: &Settings) -> u8 {
+        match self {
+            Setting::BaudRate => match settings.baud_rate {
+                None => 0,
+                Some(baud_rate) => baud_rate.code() + 1,
+            },
+            Setting::Port => match settings.port {
+                None => 0,
+                Some(port) => port as u8 + 1,
+            },
+            Setting::PollInterval => POLL_INTERVALS
+                .iter()
+                .position(|(_, seconds)| *seconds == settings.poll_interval)
+                .unwrap_or_default() as u8,
+            Setting::Logging => settings.logging as u8,
+            Setting::Units => settings.units as u8,
+        }
+    }
+
+    pub fn set_value_index(self, settings: &mut Settings, index: u8) {
+        match self {
+            Setting::BaudRate => {
+                settings.baud_rate = index
+                    .checked_sub(1)
+                    .and_then(|index| BaudRate::list().get(index as usize).copied())
+            }
+            Setting::Port => {
+                settings.port = index
+                    .checked_sub(1)
+                    .and_then(|index| Port::list().get(index as usize).copied())
+            }
+            Setting::PollInterval => {
+                settings.poll_interval = POLL_INTERVALS
+                    .get(index as usize)
+                    .map(|(_, seconds)| *seconds)
+                    .unwrap_or_default()
+            }
+            Setting::Logging => settings.logging = index != 0,
+            Setting::Units => {
+                settings.units = match index {
+                    0 => DisplayUnits::Sensor,
+                    1 => DisplayUnits::Metric,
+                    _ => DisplayUnits::Imperial,
+                }
+            }
+        }
+    }
+}
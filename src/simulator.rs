@@ -0,0 +1,160 @@
+use core::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use flipperzero::furi::{
+    self,
+    stream_buffer::{Receiver, Sender, StreamBuffer},
+    sync::Mutex,
+    thread::JoinHandle,
+    time::Duration,
+};
+use rg15_protocol::{BaudRate, Scenario, SimulatedSensor};
+
+/// Real time in ms between two simulation steps.
+const TICK: u32 = 100;
+
+/// Simulated time runs this much faster than real time, so that scenarios
+/// show some rain within a demo.
+const TIME_SCALE: u32 = 10;
+
+/// Runs a [`SimulatedSensor`] in its own thread and connects it to the app
+/// like the serial interrupt would.
+pub struct Simulator {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    // the rate the app talks at, may differ from the simulated sensor's rate
+    link_baud_rate: AtomicU32,
+    stop: AtomicBool,
+}
+
+struct State {
+    sensor: SimulatedSensor,
+    sender: Sender,
+    input: String,
+    output: String,
+}
+
+impl Simulator {
+    pub fn start(scenario: Scenario) -> (Self, Receiver) {
+        let size = NonZeroUsize::new(4096).expect("non-zero value");
+        let (sender, rx) = StreamBuffer::new(size, 1).into_stream();
+
+        let mut state = State {
+            sensor: SimulatedSensor::new(scenario),
+            sender,
+            input: String::new(),
+            output: String::new(),
+        };
+        let baud_rate = state.sensor.baud_rate;
+        state.sensor.power_up(&mut state.output);
+        state.flush(baud_rate, baud_rate);
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(state),
+            link_baud_rate: AtomicU32::new(baud_rate.rate() as u32),
+            stop: AtomicBool::new(false),
+        });
+
+        let thread = furi::thread::Builder::new().stack_size(2048).spawn({
+            let shared = shared.clone();
+            move || {
+                while !shared.stop.load(Ordering::Relaxed) {
+                    furi::thread::sleep(Duration::from_millis(TICK));
+                    let mut state = shared.state.lock();
+                    let State { sensor, output, .. } = &mut *state;
+                    sensor.advance(TICK * TIME_SCALE, output);
+                    let baud_rate = state.sensor.baud_rate;
+                    state.flush(baud_rate, shared.link_baud_rate());
+                }
+                0
+            }
+        });
+
+        let simulator = Self {
+            shared,
+            thread: Some(thread),
+        };
+        (simulator, rx)
+    }
+
+    pub fn tx(&self, buffer: &[u8]) {
+        let link_baud_rate = self.shared.link_baud_rate();
+        let mut state = self.shared.state.lock();
+        for &byte in buffer {
+            if byte != b'\n' {
+                state.input.push(byte as char);
+                continue;
+            }
+
+            // answers go out at the rate the command came in, even if it
+            // changes the rate
+            let baud_rate = state.sensor.baud_rate;
+            if baud_rate == link_baud_rate {
+                let State {
+                    sensor,
+                    input,
+                    output,
+                    ..
+                } = &mut *state;
+                sensor.receive(input, output);
+            }
+            state.input.clear();
+            state.flush(baud_rate, link_baud_rate);
+        }
+    }
+
+    pub fn set_br(&self, baud_rate: u32) {
+        self.shared
+            .link_baud_rate
+            .store(baud_rate, Ordering::Relaxed);
+    }
+
+    pub fn baud_rate(&self) -> u32 {
+        self.shared.link_baud_rate.load(Ordering::Relaxed)
+    }
+}
+
+impl Shared {
+    fn link_baud_rate(&self) -> BaudRate {
+        let baud_rate = self.link_baud_rate.load(Ordering::Relaxed);
+        BaudRate::try_from(baud_rate as u16).unwrap_or_default()
+    }
+}
+
+impl State {
+    /// Sends the pending output, garbled if both sides use different rates.
+    fn flush(&mut self, baud_rate: BaudRate, link_baud_rate: BaudRate) {
+        // what doesn't fit is dropped, just like an overrun on real hardware
+        match baud_rate == link_baud_rate {
+            true => self.sender.send(self.output.as_bytes()),
+            false => {
+                let garbled: Vec<u8> = self
+                    .output
+                    .bytes()
+                    .map(|byte| byte.rotate_left(3) | 0x80)
+                    .collect();
+                self.sender.send(&garbled)
+            }
+        };
+        self.output.clear();
+    }
+}
+
+impl Drop for Simulator {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join();
+        }
+
+        // wake up the reader to let it notice the simulator is gone
+        self.shared.state.lock().sender.send(b"\n");
+    }
+}
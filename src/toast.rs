@@ -0,0 +1,57 @@
+use alloc::{ffi::CString, string::String};
+use core::ffi::CStr;
+
+use crate::{safe::*, SCREEN_WIDTH};
+
+/// Seconds a toast stays on screen.
+const TOAST_DURATION: u32 = 3;
+
+const TOAST_X: i32 = 4;
+const TOAST_Y: i32 = 14;
+const TOAST_WIDTH: usize = SCREEN_WIDTH as usize - 2 * TOAST_X as usize;
+const TOAST_HEIGHT: usize = 26;
+const PADDING: i32 = 4;
+
+/// Characters of the text that fit into the frame.
+const MAX_TEXT_LEN: usize = 22;
+
+/// Short two line message drawn on top of a view for a few seconds.
+#[derive(Debug)]
+pub struct Toast {
+    title: CString,
+    text: CString,
+    remaining: u32,
+}
+
+impl Toast {
+    pub fn new(title: &CStr, text: &str) -> Self {
+        // the toast has a single line for the text, cut it to fit the frame
+        let text: String = text
+            .chars()
+            .filter(|c| *c != '\0')
+            .take(MAX_TEXT_LEN)
+            .collect();
+        Self {
+            title: CString::from(title),
+            text: CString::new(text).expect("nul bytes were removed"),
+            remaining: TOAST_DURATION,
+        }
+    }
+
+    /// Lets one second pass, returns whether the toast is still shown.
+    pub fn tick(&mut self) -> bool {
+        self.remaining = self.remaining.saturating_sub(1);
+        self.remaining > 0
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas) {
+        // clear what's below first
+        canvas.invert_color();
+        canvas.draw_box(TOAST_X, TOAST_Y, TOAST_WIDTH, TOAST_HEIGHT);
+        canvas.invert_color();
+        canvas.draw_frame(TOAST_X, TOAST_Y, TOAST_WIDTH, TOAST_HEIGHT);
+
+        canvas.draw_str(TOAST_X + PADDING, TOAST_Y + 11, &self.title);
+        canvas.draw_str(TOAST_X + PADDING, TOAST_Y + 21, &self.text);
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::sync::Arc;
+use flipperzero::furi::sync::Mutex;
+use rg15_protocol::{Command, PollTracker};
+
+use crate::{
+    capture::Replay,
+    safe::{serial_marker::Initialized, *},
+    simulator::Simulator,
+};
+
+/// Where the data of the rx thread comes from.
+pub enum Link {
+    Serial(SerialHandle<Initialized>),
+    /// Replays a capture, commands are dropped as nothing could answer them.
+    Replay(Replay),
+    /// A sensor simulated inside the app.
+    Simulator(Simulator),
+}
+
+/// Where commands go to, keeps count of the polls sent through it.
+///
+/// Commands are sent from several threads, one at a time so that their bytes
+/// don't mix.
+pub struct Transport {
+    link: Link,
+    polls: Arc<PollTracker>,
+    tx_lock: Mutex<()>,
+}
+
+impl Transport {
+    pub fn new(link: Link) -> Self {
+        Self {
+            link,
+            polls: Arc::new(PollTracker::new()),
+            tx_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sends `buffer` in one piece, commands have to be sent with their line
+    /// ending in one call.
+    pub fn tx(&self, buffer: &[u8]) {
+        let _tx = self.tx_lock.lock();
+        let cmd = core::str::from_utf8(buffer)
+            .ok()
+            .and_then(Command::from_text);
+        if let Some(cmd) = cmd {
+            self.polls.sent(cmd);
+        }
+
+        match &self.link {
+            Link::Serial(serial_handle) => serial_handle.tx(buffer),
+            Link::Replay(_) => (),
+            Link::Simulator(simulator) => simulator.tx(buffer),
+        }
+    }
+
+    pub fn set_br(&self, baud_rate: u32) {
+        // not in the middle of a command
+        let _tx = self.tx_lock.lock();
+        match &self.link {
+            Link::Serial(serial_handle) => serial_handle.set_br(baud_rate),
+            Link::Replay(_) => (),
+            Link::Simulator(simulator) => simulator.set_br(baud_rate),
+        }
+    }
+
+    pub fn baud_rate(&self) -> u32 {
+        match &self.link {
+            Link::Serial(serial_handle) => serial_handle.baud_rate(),
+            Link::Replay(replay) => replay.baud_rate(),
+            Link::Simulator(simulator) => simulator.baud_rate(),
+        }
+    }
+
+    pub fn is_serial(&self) -> bool {
+        matches!(self.link, Link::Serial(_))
+    }
+
+    /// Receive errors of the serial port, `None` for other sources.
+    pub fn rx_errors(&self) -> Option<Arc<RxErrors>> {
+        match &self.link {
+            Link::Serial(serial_handle) => serial_handle.rx_errors(),
+            Link::Replay(_) | Link::Simulator(_) => None,
+        }
+    }
+
+    /// The polls sent so far, for the rx thread to match the readings with,
+    /// `None` for replays as the polls of a capture weren't recorded.
+    pub fn polls(&self) -> Option<Arc<PollTracker>> {
+        match self.link {
+            Link::Replay(_) => None,
+            Link::Serial(_) | Link::Simulator(_) => Some(self.polls.clone()),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::sync::Arc;
+use flipperzero::furi::{self, sync::Mutex, thread::JoinHandle, time::Duration};
+
+/// Stack of the worker thread, enough to format lines and write files.
+const STACK_SIZE: usize = 4096;
+
+/// Time in ms a sleeping job takes at most to notice it was cancelled.
+const SLEEP_SLICE: u32 = 50;
+
+/// Runs one job at a time on its own thread, so that the view dispatcher
+/// keeps drawing and handling input while e.g. a command waits for its
+/// answer.
+///
+/// Has to be stopped from another thread than its own, before the last
+/// reference to what the jobs use goes away.
+pub struct Worker {
+    thread: Mutex<Option<JoinHandle>>,
+    busy: Arc<AtomicBool>,
+    cancel: Cancel,
+}
+
+/// Tells a job to stop early, it checks between its steps.
+#[derive(Clone)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Sleeps for `millis`, or until cancelled.
+    pub fn sleep(&self, millis: u32) {
+        let mut remaining = millis;
+        while remaining > 0 && !self.is_cancelled() {
+            let slice = remaining.min(SLEEP_SLICE);
+            furi::thread::sleep(Duration::from_millis(slice));
+            remaining -= slice;
+        }
+    }
+}
+
+impl Worker {
+    pub fn new() -> Self {
+        Self {
+            thread: Mutex::new(None),
+            busy: Arc::new(AtomicBool::new(false)),
+            cancel: Cancel(Arc::new(AtomicBool::new(false))),
+        }
+    }
+
+    /// Starts `job` unless another one is still running, returns whether it
+    /// was started.
+    pub fn start(&self, job: impl FnOnce(&Cancel) + Send + 'static) -> bool {
+        let mut thread = self.thread.lock();
+        if self.busy.swap(true, Ordering::Acquire) {
+            return false;
+        }
+        // the previous job is done, its thread only has to be cleaned up
+        if let Some(thread) = thread.take() {
+            thread.join();
+        }
+
+        self.cancel.0.store(false, Ordering::Relaxed);
+        let busy = self.busy.clone();
+        let cancel = self.cancel.clone();
+        let spawned = furi::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                job(&cancel);
+                busy.store(false, Ordering::Release);
+                0
+            });
+        *thread = Some(spawned);
+        true
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::Acquire)
+    }
+
+    /// Asks the running job to stop.
+    pub fn cancel(&self) {
+        self.cancel.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Cancels the running job and waits until it's done.
+    pub fn stop(&self) {
+        self.cancel();
+        if let Some(thread) = self.thread.lock().take() {
+            thread.join();
+        }
+    }
+}